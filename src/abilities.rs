@@ -3,32 +3,105 @@ use std::{
     ops::{Add, AddAssign},
 };
 
+use anyhow::bail;
 use serde::{Deserialize, Serialize};
 
+/// Maximum score an ability can reach with an ability score improvement
+pub const ABILITY_SCORE_MAX: u8 = 20;
+/// Maximum score of strength and constitution with https://www.dnd5eapi.co/api/features/primal-champion
+pub const PRIMAL_CHAMPION_SCORE_MAX: u8 = 24;
+
+/// Where a modifier of an ability score comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum AbilityScoreSource {
+    /// Index from https://www.dnd5eapi.co/api/races/ or https://www.dnd5eapi.co/api/subraces/,
+    /// see Character::set_race
+    Race(String),
+    /// Ability score improvement gained at a level of a class
+    AbilityScoreImprovement {
+        /// Index from https://www.dnd5eapi.co/api/classes/
+        class: String,
+        level: u8,
+    },
+    /// Index from https://www.dnd5eapi.co/api/feats/
+    Feat(String),
+    /// Index from https://www.dnd5eapi.co/api/features/, e.g. primal-champion
+    Feature(String),
+    /// Index from https://www.dnd5eapi.co/api/magic-items/
+    MagicItem(String),
+}
+
+/// How a modifier changes an ability score
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum AbilityScoreChange {
+    /// Adds to the score
+    Bonus(i8),
+    /// Sets the score to the value unless it is already higher,
+    /// e.g. https://www.dnd5eapi.co/api/magic-items/gauntlets-of-ogre-power
+    Set(u8),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct AbilityScoreModifier {
+    pub source: AbilityScoreSource,
+    pub change: AbilityScoreChange,
+}
+
+/// How much a source adds to the total of an ability score, used to explain it
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct AbilityScoreContribution {
+    pub source: AbilityScoreSource,
+    pub value: i16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct AbilityScoreBreakdown {
+    pub base: u8,
+    pub contributions: Vec<AbilityScoreContribution>,
+    pub total: u8,
+}
+
 #[derive(Debug, Default, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 #[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct AbilityScore {
+    /// The base score, rolled or bought, without any modifier
     pub score: u8,
     pub proficiency: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub modifiers: Vec<AbilityScoreModifier>,
 }
 
 impl Add for AbilityScore {
     type Output = Self;
 
-    fn add(self, other: Self) -> Self {
-        Self {
-            score: self.score + other.score,
-            proficiency: self.proficiency || other.proficiency,
-        }
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
     }
 }
 
 impl AddAssign for AbilityScore {
     fn add_assign(&mut self, other: Self) {
-        self.score += other.score;
+        self.score = self.score.saturating_add(other.score);
         self.proficiency = self.proficiency || other.proficiency;
+        self.modifiers.extend(other.modifiers);
     }
 }
 
@@ -46,12 +119,119 @@ impl Sum for AbilityScore {
 
 impl AbilityScore {
     pub fn new(score: u8, proficiency: bool) -> Self {
-        Self { score, proficiency }
+        Self {
+            score,
+            proficiency,
+            modifiers: vec![],
+        }
     }
+
+    /// The base score with the bonuses, ignoring the modifiers that set the score
+    fn score_with_bonuses(&self) -> u8 {
+        let bonuses: i16 = self
+            .modifiers
+            .iter()
+            .filter_map(|modifier| match modifier.change {
+                AbilityScoreChange::Bonus(bonus) => Some(bonus as i16),
+                AbilityScoreChange::Set(_) => None,
+            })
+            .sum();
+
+        (self.score as i16 + bonuses).clamp(0, u8::MAX as i16) as u8
+    }
+
+    /// Returns the score with all the modifiers applied
+    pub fn total(&self) -> u8 {
+        self.modifiers
+            .iter()
+            .filter_map(|modifier| match modifier.change {
+                AbilityScoreChange::Set(score) => Some(score),
+                AbilityScoreChange::Bonus(_) => None,
+            })
+            .fold(self.score_with_bonuses(), u8::max)
+    }
+
+    /// Returns the base score and what every source adds to it, for tooltips
+    /// Modifiers setting the score only contribute what they add over the bonuses
+    pub fn breakdown(&self) -> AbilityScoreBreakdown {
+        let with_bonuses = self.score_with_bonuses();
+        let total = self.total();
+
+        let mut set_applied = false;
+        let contributions = self
+            .modifiers
+            .iter()
+            .map(|modifier| {
+                let value = match modifier.change {
+                    AbilityScoreChange::Bonus(bonus) => bonus as i16,
+                    AbilityScoreChange::Set(score) if score == total && !set_applied => {
+                        set_applied = true;
+                        total as i16 - with_bonuses as i16
+                    }
+                    AbilityScoreChange::Set(_) => 0,
+                };
+
+                AbilityScoreContribution {
+                    source: modifier.source.clone(),
+                    value,
+                }
+            })
+            .collect();
+
+        AbilityScoreBreakdown {
+            base: self.score,
+            contributions,
+            total,
+        }
+    }
+
+    pub fn add_modifier(&mut self, source: AbilityScoreSource, change: AbilityScoreChange) {
+        self.modifiers.push(AbilityScoreModifier { source, change });
+    }
+
+    /// Removes all the modifiers of the source, e.g. when unequipping a magic item
+    pub fn remove_modifiers(&mut self, source: &AbilityScoreSource) {
+        self.modifiers.retain(|modifier| &modifier.source != source);
+    }
+
+    /// Returns true if the bonus would not take the score over the maximum,
+    /// modifiers setting the score are not taken into account
+    pub fn can_increase(&self, amount: u8, max: u8) -> bool {
+        self.score_with_bonuses()
+            .checked_add(amount)
+            .is_some_and(|score| score <= max)
+    }
+
+    /// Adds a bonus from the source to the score
+    /// Returns an error without changing the score if it would go over the maximum
+    pub fn increase(
+        &mut self,
+        amount: u8,
+        max: u8,
+        source: AbilityScoreSource,
+    ) -> anyhow::Result<()> {
+        if !self.can_increase(amount, max) {
+            bail!("The ability score can't go over {}", max)
+        }
+
+        // Two improvements of the same ability at once are kept as a single +2
+        match self.modifiers.iter_mut().find(|modifier| {
+            modifier.source == source && matches!(modifier.change, AbilityScoreChange::Bonus(_))
+        }) {
+            Some(AbilityScoreModifier {
+                change: AbilityScoreChange::Bonus(bonus),
+                ..
+            }) => *bonus = bonus.saturating_add(amount as i8),
+            _ => self.add_modifier(source, AbilityScoreChange::Bonus(amount as i8)),
+        }
+
+        Ok(())
+    }
+
     /// Returns the modifier of the ability score
     /// if you want to add the proficiency bonus, pass it as an argument otherwise pass 0
     pub fn modifier(&self, proficiency_bonus: u8) -> i8 {
-        ((self.score as i8 - 10) as f32 / 2f32).floor() as i8
+        ((self.total() as i16 - 10) as f32 / 2f32).floor() as i8
             + if self.proficiency {
                 proficiency_bonus as i8
             } else {
@@ -82,6 +262,49 @@ pub struct Abilities {
     pub charisma: AbilityScore,
 }
 
+impl Abilities {
+    /// Returns the ability score from its index in https://www.dnd5eapi.co/api/ability-scores/
+    /// (e.g. `str`) or from its full name (e.g. `strength`)
+    pub fn get(&self, index: &str) -> Option<&AbilityScore> {
+        match index {
+            "str" | "strength" => Some(&self.strength),
+            "dex" | "dexterity" => Some(&self.dexterity),
+            "con" | "constitution" => Some(&self.constitution),
+            "int" | "intelligence" => Some(&self.intelligence),
+            "wis" | "wisdom" => Some(&self.wisdom),
+            "cha" | "charisma" => Some(&self.charisma),
+            _ => None,
+        }
+    }
+
+    /// Mutable version of Abilities::get
+    pub fn get_mut(&mut self, index: &str) -> Option<&mut AbilityScore> {
+        match index {
+            "str" | "strength" => Some(&mut self.strength),
+            "dex" | "dexterity" => Some(&mut self.dexterity),
+            "con" | "constitution" => Some(&mut self.constitution),
+            "int" | "intelligence" => Some(&mut self.intelligence),
+            "wis" | "wisdom" => Some(&mut self.wisdom),
+            "cha" | "charisma" => Some(&mut self.charisma),
+            _ => None,
+        }
+    }
+
+    /// Removes the modifiers of the source from every ability
+    pub fn remove_modifiers(&mut self, source: &AbilityScoreSource) {
+        for ability in [
+            &mut self.strength,
+            &mut self.dexterity,
+            &mut self.constitution,
+            &mut self.intelligence,
+            &mut self.wisdom,
+            &mut self.charisma,
+        ] {
+            ability.remove_modifiers(source);
+        }
+    }
+}
+
 impl Add for Abilities {
     type Output = Self;
 
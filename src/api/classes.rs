@@ -1,15 +1,20 @@
 use super::shared::schema;
 use crate::GRAPHQL_API_URL;
+use crate::abilities::{
+    ABILITY_SCORE_MAX, Abilities, AbilityScoreChange, AbilityScoreSource, PRIMAL_CHAMPION_SCORE_MAX,
+};
 use crate::api::classes::CustomLevelFeatureType::Ignored;
 use crate::api::shared::ApiError;
-use crate::classes::{Class, Classes, UsableSlots};
+use crate::classes::{Class, ClassResource, Classes, RestType, SpellSlotCreation, UsableSlots};
+use anyhow::bail;
 use cynic::http::ReqwestExt;
 use cynic::{QueryBuilder, impl_scalar};
-use futures::StreamExt;
+use futures::TryStreamExt;
 use lazy_static::lazy_static;
 use reqwest::Client;
 use serde_json::json;
 use std::collections::HashMap;
+use std::sync::Mutex;
 
 #[derive(cynic::QueryVariables, Debug)]
 struct SpellcastingAbilityQueryVariables {
@@ -102,13 +107,153 @@ impl Into<UsableSlots> for LevelSpellcasting {
     }
 }
 
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Query", variables = "SpellcastingQueryVariables")]
+pub struct ClassSpecificQuery {
+    #[arguments(index: $ index)]
+    pub level: Option<LevelClassSpecific>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Level")]
+pub struct LevelClassSpecific {
+    #[cynic(rename = "class_specific")]
+    pub class_specific: Option<ClassSpecific>,
+}
+
+#[derive(cynic::InlineFragments, Debug, Clone)]
+pub enum ClassSpecific {
+    BarbarianSpecific(BarbarianSpecific),
+    BardSpecific(BardSpecific),
+    ClericSpecific(ClericSpecific),
+    FighterSpecific(FighterSpecific),
+    MonkSpecific(MonkSpecific),
+    SorcererSpecific(SorcererSpecific),
+    WizardSpecific(WizardSpecific),
+    #[cynic(fallback)]
+    Other,
+}
+
+#[derive(cynic::QueryFragment, Debug, Clone)]
+pub struct BarbarianSpecific {
+    #[cynic(rename = "rage_count")]
+    pub rage_count: i32,
+    #[cynic(rename = "rage_damage_bonus")]
+    pub rage_damage_bonus: i32,
+}
+
+#[derive(cynic::QueryFragment, Debug, Clone)]
+pub struct BardSpecific {
+    #[cynic(rename = "bardic_inspiration_die")]
+    pub bardic_inspiration_die: i32,
+}
+
+#[derive(cynic::QueryFragment, Debug, Clone)]
+pub struct ClericSpecific {
+    #[cynic(rename = "channel_divinity_charges")]
+    pub channel_divinity_charges: i32,
+}
+
+#[derive(cynic::QueryFragment, Debug, Clone)]
+pub struct FighterSpecific {
+    #[cynic(rename = "action_surges")]
+    pub action_surges: i32,
+    #[cynic(rename = "indomitable_uses")]
+    pub indomitable_uses: i32,
+    #[cynic(rename = "extra_attacks")]
+    pub extra_attacks: i32,
+}
+
+#[derive(cynic::QueryFragment, Debug, Clone)]
+pub struct MonkSpecific {
+    #[cynic(rename = "ki_points")]
+    pub ki_points: i32,
+    #[cynic(rename = "unarmored_movement")]
+    pub unarmored_movement: i32,
+}
+
+#[derive(cynic::QueryFragment, Debug, Clone)]
+pub struct SorcererSpecific {
+    #[cynic(rename = "sorcery_points")]
+    pub sorcery_points: i32,
+    #[cynic(rename = "creating_spell_slots")]
+    pub creating_spell_slots: Vec<LevelSpellSlotCreation>,
+}
+
+#[derive(cynic::QueryFragment, Debug, Copy, Clone)]
+#[cynic(graphql_type = "SpellSlotCreation")]
+pub struct LevelSpellSlotCreation {
+    #[cynic(rename = "sorcery_point_cost")]
+    pub sorcery_point_cost: i32,
+    #[cynic(rename = "spell_slot_level")]
+    pub spell_slot_level: i32,
+}
+
+impl From<LevelSpellSlotCreation> for SpellSlotCreation {
+    fn from(creation: LevelSpellSlotCreation) -> Self {
+        SpellSlotCreation {
+            spell_slot_level: creation.spell_slot_level as u8,
+            sorcery_point_cost: creation.sorcery_point_cost as u8,
+        }
+    }
+}
+
+#[derive(cynic::QueryFragment, Debug, Clone)]
+pub struct WizardSpecific {
+    #[cynic(rename = "arcane_recovery_levels")]
+    pub arcane_recovery_levels: i32,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(
+    graphql_type = "Query",
+    variables = "SpellcastingAbilityQueryVariables"
+)]
+struct SubclassesQuery {
+    #[arguments(index: $ index)]
+    pub class: Option<ClassSubclasses>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Class")]
+struct ClassSubclasses {
+    pub subclasses: Vec<SubclassIndex>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Subclass")]
+pub struct SubclassIndex {
+    pub index: String,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Query")]
+struct FeatsQuery {
+    pub feats: Option<Vec<Feat>>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+struct Feat {
+    pub index: String,
+    pub prerequisites: Vec<AbilityScorePrerequisite>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+struct AbilityScorePrerequisite {
+    #[cynic(rename = "ability_score")]
+    pub ability_score: AbilityScore,
+    #[cynic(rename = "minimum_score")]
+    pub minimum_score: i32,
+}
+
 #[derive(cynic::QueryVariables, Debug)]
 pub struct LevelFeaturesQueryVariables {
     pub class: Option<StringFilter>,
+    pub subclass: Option<StringFilter>,
     pub level: Option<LevelFilter>,
 }
 
-#[derive(serde::Serialize, Debug)]
+#[derive(serde::Serialize, Debug, Clone)]
 pub struct LevelFilter {
     pub gt: Option<u8>,
     pub gte: Option<u8>,
@@ -120,13 +265,15 @@ impl_scalar!(LevelFilter, schema::IntFilter);
 #[derive(cynic::QueryFragment, Debug)]
 #[cynic(graphql_type = "Query", variables = "LevelFeaturesQueryVariables")]
 pub struct LevelFeaturesQuery {
-    #[arguments(class: $ class, level: $level )]
+    #[arguments(class: $ class, subclass: $subclass, level: $level )]
     pub features: Option<Vec<Feature>>,
 }
 
 #[derive(cynic::QueryFragment, Debug)]
 pub struct Feature {
     pub index: String,
+    pub level: i32,
+    pub subclass: Option<SubclassIndex>,
 }
 
 #[derive(cynic::Scalar, Debug, Clone)]
@@ -136,8 +283,17 @@ pub struct StringFilter(pub String);
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 pub enum ChoosableCustomLevelFeature {
-    /// Ask the user to spend 2 points in any ability score
-    AbilityScoreImprovement,
+    /// Ask the user to spend 2 points in any ability score or to take a feat instead,
+    /// answered with two of the abilities options or a single feat, see Character::apply_choice
+    AbilityScoreImprovement {
+        /// Class level that granted the improvement, recorded as the source of the modifiers
+        level: u8,
+        /// The +1 options of the abilities that are still under the maximum score
+        abilities: Vec<ChoosableCustomLevelFeatureOption>,
+        /// Indexes from https://www.dnd5eapi.co/api/feats/ whose prerequisites are met,
+        /// Character::apply_choice rejects the ones the character already has
+        feats: Vec<String>,
+    },
     /// https://dnd5eapi.rpgmaster.ai/api/2014/features/hunters-prey
     HuntersPrey,
     /// https://dnd5eapi.rpgmaster.ai/api/2014/features/defensive-tactics
@@ -187,9 +343,16 @@ pub enum ChoosableCustomLevelFeature {
     EldritchInvocations,
     /// https://dnd5eapi.rpgmaster.ai/api/2014/features/dragon-ancestor
     DragonAncestor,
+    /// Used for the subclass choice of every class, e.g.
+    /// https://www.dnd5eapi.co/api/features/primal-path
+    /// https://www.dnd5eapi.co/api/features/otherworldly-patron
+    /// Holds the indexes of the subclasses available from https://www.dnd5eapi.co/api/classes/{index}/subclasses
+    Subclass(Vec<String>),
+    /// https://www.dnd5eapi.co/api/features/circle-of-the-land
+    CircleOfTheLandTerrain,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 pub enum ChoosableCustomLevelFeatureOption {
@@ -321,12 +484,47 @@ pub enum ChoosableCustomLevelFeatureOption {
     DragonAncestorSilverColdDamage,
     #[serde(rename = "dragon-ancestor-white---cold-damage")]
     DragonAncestorWhiteColdDamage,
+
+    CircleOfTheLandArctic,
+    CircleOfTheLandCoast,
+    CircleOfTheLandDesert,
+    CircleOfTheLandForest,
+    CircleOfTheLandGrassland,
+    CircleOfTheLandMountain,
+    CircleOfTheLandSwamp,
+    CircleOfTheLandUnderdark,
+
+    /// Index from https://www.dnd5eapi.co/api/feats/
+    /// Serialized as its bare index, like as_index_str, it is never deserialized since any
+    /// string would match, see ChoosableCustomLevelFeature::option_from_index_str
+    #[serde(untagged, skip_deserializing)]
+    Feat(String),
+
+    /// Index from https://www.dnd5eapi.co/api/subclasses/
+    /// Serialized as its bare index, like as_index_str, it is never deserialized since any
+    /// string would match, see ChoosableCustomLevelFeature::option_from_index_str
+    #[serde(untagged, skip_deserializing)]
+    Subclass(String),
 }
 
+/// Options of an ability score improvement, one for each ability
+pub const ABILITY_SCORE_IMPROVEMENT_OPTIONS: [ChoosableCustomLevelFeatureOption; 6] = [
+    ChoosableCustomLevelFeatureOption::StrengthPlusOne,
+    ChoosableCustomLevelFeatureOption::DexterityPlusOne,
+    ChoosableCustomLevelFeatureOption::ConstitutionPlusOne,
+    ChoosableCustomLevelFeatureOption::IntelligencePlusOne,
+    ChoosableCustomLevelFeatureOption::WisdomPlusOne,
+    ChoosableCustomLevelFeatureOption::CharismaPlusOne,
+];
+
 impl ChoosableCustomLevelFeatureOption {
     #[cfg(feature = "serde")]
     pub fn as_index_str(&self) -> &str {
-        serde_variant::to_variant_name(self).unwrap()
+        match self {
+            ChoosableCustomLevelFeatureOption::Subclass(index)
+            | ChoosableCustomLevelFeatureOption::Feat(index) => index,
+            _ => serde_variant::to_variant_name(self).unwrap(),
+        }
     }
 
     #[cfg(feature = "serde")]
@@ -352,21 +550,33 @@ impl ChoosableCustomLevelFeature {
         serde_variant::to_variant_name(self).unwrap()
     }
 
+    /// Finds the option of this feature from its index, unlike
+    /// ChoosableCustomLevelFeatureOption::from_index_str it can tell feats and subclasses apart
+    #[cfg(feature = "serde")]
+    pub fn option_from_index_str(&self, index: &str) -> Option<ChoosableCustomLevelFeatureOption> {
+        let feats = match self {
+            ChoosableCustomLevelFeature::AbilityScoreImprovement { feats, .. } => feats.clone(),
+            _ => vec![],
+        };
+
+        self.to_options()
+            .into_iter()
+            .flatten()
+            .chain(
+                feats
+                    .into_iter()
+                    .map(ChoosableCustomLevelFeatureOption::Feat),
+            )
+            .find(|option| option.as_index_str() == index)
+    }
+
     pub fn to_options(&self) -> Vec<Vec<ChoosableCustomLevelFeatureOption>> {
         use ChoosableCustomLevelFeatureOption::*;
 
         match self {
-            ChoosableCustomLevelFeature::AbilityScoreImprovement => {
-                let ability_names = vec![
-                    StrengthPlusOne,
-                    DexterityPlusOne,
-                    ConstitutionPlusOne,
-                    IntelligencePlusOne,
-                    WisdomPlusOne,
-                    CharismaPlusOne,
-                ];
-
-                vec![ability_names.clone(), ability_names]
+            // A single feat can be chosen instead of the two abilities
+            ChoosableCustomLevelFeature::AbilityScoreImprovement { abilities, .. } => {
+                vec![abilities.clone(), abilities.clone()]
             }
             ChoosableCustomLevelFeature::WarlockPact => {
                 vec![vec![PactOfTheChain, PactOfTheBlade, PactOfTheTome]]
@@ -515,6 +725,21 @@ impl ChoosableCustomLevelFeature {
 
                 vec![all_eldritch_invocations.clone(), all_eldritch_invocations]
             }
+            ChoosableCustomLevelFeature::Subclass(subclasses) => {
+                vec![subclasses.iter().cloned().map(Subclass).collect()]
+            }
+            ChoosableCustomLevelFeature::CircleOfTheLandTerrain => {
+                vec![vec![
+                    CircleOfTheLandArctic,
+                    CircleOfTheLandCoast,
+                    CircleOfTheLandDesert,
+                    CircleOfTheLandForest,
+                    CircleOfTheLandGrassland,
+                    CircleOfTheLandMountain,
+                    CircleOfTheLandSwamp,
+                    CircleOfTheLandUnderdark,
+                ]]
+            }
             ChoosableCustomLevelFeature::DragonAncestor => {
                 vec![vec![
                     DragonAncestorBlackAcidDamage,
@@ -540,6 +765,8 @@ pub enum SheetLevelFeatureType {
 
 pub enum CustomLevelFeatureType {
     Choosable(ChoosableCustomLevelFeature),
+    /// The options are fetched from the API when the feature is reached
+    SubclassChoice,
     Sheet(SheetLevelFeatureType),
     Passive,
     Ignored,
@@ -551,7 +778,6 @@ impl CustomLevelFeatureType {
         use CustomLevelFeatureType::*;
         use SheetLevelFeatureType::*;
         match index.as_str() {
-            // Ignore all subclass choices since we have only one subclass per class
             "bard-college"
             | "divine-domain"
             | "monastic-tradition"
@@ -562,7 +788,8 @@ impl CustomLevelFeatureType {
             | "primal-path"
             | "martial-archetype"
             | "roguish-archetype"
-            | "otherworldly-patron" => Some(Ignored),
+            | "otherworldly-patron"
+            | "arcane-tradition" => Some(SubclassChoice),
             "pact-boon" => Some(Choosable(WarlockPact)),
             "additional-fighting-style" => Some(Choosable(AdditionalFighterFightingStyle)),
             "fighter-fighting-style" => Some(Choosable(FighterFightingStyle)),
@@ -584,7 +811,6 @@ impl CustomLevelFeatureType {
             // TODO: Implement https://www.dnd5eapi.co/api/features/diamond-soul
             "diamond-soul" => Some(Passive),
             "arcane-recovery"
-            | "arcane-tradition"
             | "archdruid"
             | "aura-improvements"
             | "aura-of-courage"
@@ -665,8 +891,8 @@ impl CustomLevelFeatureType {
             | "second-story-work"
             | "primeval-awareness"
             | "beast-spells" => Some(Passive),
-            // ignored until implementation?
-            "oath-spells" => Some(Ignored),
+            // The spells are added to subclass_spells_index
+            "oath-spells" => Some(Passive),
             "natural-recovery" => Some(Ignored),
             "eldritch-invocations" => Some(Choosable(EldritchInvocations)),
             x if x.starts_with("metamagic-") => {
@@ -687,14 +913,12 @@ impl CustomLevelFeatureType {
             x if x.starts_with("spellcasting-") => Some(Ignored),
             // Ignore all eldritch invocations since they are unlocked using invocation known table
             x if x.starts_with("eldritch-invocation-") => Some(Ignored),
-            // Ignore all circle-spells until implementation
-            x if x.starts_with("circle-spells-") => Some(Ignored),
-            // Ignore all circle of the land until implementation
-            x if x.starts_with("circle-of-the-land") => Some(Ignored),
-            // Ignore all domain spells until implementation
-            x if x.starts_with("domain-spells-") => Some(Ignored),
-            // sorcery points not yet implemented
-            x if x.starts_with("flexible-casting-") => Some(Ignored),
+            x if x.starts_with("circle-spells-") => Some(Passive),
+            "circle-of-the-land" => Some(Choosable(CircleOfTheLandTerrain)),
+            // Ignore the terrains since they are chosen from circle-of-the-land
+            x if x.starts_with("circle-of-the-land-") => Some(Ignored),
+            x if x.starts_with("domain-spells-") => Some(Passive),
+            x if x.starts_with("flexible-casting-") => Some(Passive),
             "dragon-ancestor" => Some(Choosable(DragonAncestor)),
             x if x.starts_with("dragon-ancestor-") => Some(Ignored),
             "defensive-tactics" => Some(Choosable(DefensiveTactics)),
@@ -707,7 +931,11 @@ impl CustomLevelFeatureType {
             // Ignore pacts from patc-boon
             x if x.starts_with("pact-of-the-") => Some(Ignored),
             x if x.contains("ability-score-improvement") => {
-                Some(Choosable(AbilityScoreImprovement))
+                Some(Choosable(AbilityScoreImprovement {
+                    level: 0,
+                    abilities: ABILITY_SCORE_IMPROVEMENT_OPTIONS.to_vec(),
+                    feats: vec![],
+                }))
             }
             x if x.starts_with("fighting-style-") => Some(Ignored),
             x if x.starts_with("fighter-fighting-style-") => Some(Ignored),
@@ -718,18 +946,20 @@ impl CustomLevelFeatureType {
 }
 
 impl Classes {
-    pub(super) async fn new_day(&mut self) {
-        futures::stream::iter(self.0.values_mut())
-            .for_each_concurrent(None, |class| class.new_day())
-            .await;
+    pub(super) async fn new_day(&mut self) -> Result<(), ApiError> {
+        futures::stream::iter(self.0.values_mut().map(Ok))
+            .try_for_each_concurrent(None, |class| class.new_day())
+            .await
     }
 }
 
 impl Class {
-    pub(super) async fn new_day(&mut self) {
+    pub(super) async fn new_day(&mut self) -> Result<(), ApiError> {
         use crate::classes::ClassSpellCasting::*;
 
-        let index = self.index().to_string();
+        // The maximums are fetched first, a failed request leaves the class untouched
+        let spellcasting_slots = self.get_spellcasting_slots().await?;
+        self.update_resources().await?;
 
         if let Some(spell_casting) = &mut self.1.spell_casting {
             match spell_casting {
@@ -746,18 +976,26 @@ impl Class {
                     *pending_preparation = true;
                     spells_prepared_index.clear();
                 }
-                KnowledgeAlreadyPrepared { usable_slots, .. } => {
-                    if let Ok(Some(spellcasting_slots)) =
-                        get_spellcasting_slots(index.as_str(), self.1.level).await
-                    {
-                        *usable_slots = spellcasting_slots.into();
-                    }
-                }
+                KnowledgeAlreadyPrepared { .. } => {}
             }
         }
+
+        if let (Some(spellcasting_slots), Some(usable_slots)) =
+            (spellcasting_slots, self.1.usable_slots_mut())
+        {
+            *usable_slots = spellcasting_slots.into();
+        }
+
+        self.1.rest(RestType::Long);
+
+        Ok(())
     }
 
     pub async fn get_spellcasting_ability_index(&self) -> Result<String, ApiError> {
+        if let Some(ability) = self.spellcasting_ability() {
+            return Ok(ability.to_string());
+        }
+
         let op = SpellcastingAbilityQuery::build(SpellcastingAbilityQueryVariables {
             index: Some(self.index().to_string()),
         });
@@ -782,64 +1020,258 @@ impl Class {
         get_spellcasting_slots(self.index(), self.1.level).await
     }
 
-    pub async fn set_level(
-        &mut self,
-        new_level: u8,
-    ) -> Result<Vec<ChoosableCustomLevelFeature>, ApiError> {
+    pub async fn get_class_specific(&self) -> Result<Option<ClassSpecific>, ApiError> {
+        get_class_specific(self.index(), self.1.level).await
+    }
+
+    /// Updates the maximum of the class resources to the values of the current level
+    /// Resources that the class gains at this level start unused
+    pub async fn update_resources(&mut self) -> Result<(), ApiError> {
+        use crate::classes::ClassResourceType::*;
+        use crate::classes::RestType::*;
+
+        let Some(class_specific) = self.get_class_specific().await? else {
+            return Ok(());
+        };
+
+        let resources = match class_specific {
+            ClassSpecific::BarbarianSpecific(specific) => {
+                vec![(Rage, ClassResource::new(specific.rage_count as u16, Long))]
+            }
+            ClassSpecific::BardSpecific(specific) => {
+                let uses = self.1.abilities.lock().unwrap().charisma.modifier(0).max(1);
+                // https://www.dnd5eapi.co/api/features/font-of-inspiration
+                let restored_on = if self.1.level >= 5 { Short } else { Long };
+
+                vec![(
+                    BardicInspiration,
+                    ClassResource {
+                        die: Some(specific.bardic_inspiration_die as u8),
+                        ..ClassResource::new(uses as u16, restored_on)
+                    },
+                )]
+            }
+            ClassSpecific::ClericSpecific(specific) => vec![(
+                ChannelDivinity,
+                ClassResource::new(specific.channel_divinity_charges as u16, Short),
+            )],
+            ClassSpecific::FighterSpecific(specific) => {
+                self.1.fighter_extra_attacks = Some(specific.extra_attacks.max(0) as u8);
+
+                vec![
+                    (
+                        ActionSurge,
+                        ClassResource::new(specific.action_surges as u16, Short),
+                    ),
+                    (
+                        Indomitable,
+                        ClassResource::new(specific.indomitable_uses as u16, Long),
+                    ),
+                ]
+            }
+            ClassSpecific::MonkSpecific(specific) => {
+                self.1.monk_unarmored_movement = Some(specific.unarmored_movement.max(0) as u8);
+
+                vec![(Ki, ClassResource::new(specific.ki_points as u16, Short))]
+            }
+            ClassSpecific::WizardSpecific(specific) => {
+                self.1.wizard_arcane_recovery_levels = Some(specific.arcane_recovery_levels as u8);
+
+                vec![(ArcaneRecovery, ClassResource::new(1, Long))]
+            }
+            ClassSpecific::SorcererSpecific(specific) => {
+                self.1.sorcerer_slot_creation = Some(
+                    specific
+                        .creating_spell_slots
+                        .into_iter()
+                        .map(Into::into)
+                        .collect(),
+                );
+
+                vec![(
+                    SorceryPoints,
+                    ClassResource::new(specific.sorcery_points as u16, Long),
+                )]
+            }
+            // The class specific data of the paladin only has the aura range, it gains one
+            // channel divinity per short rest with its sacred oath
+            // https://www.dnd5eapi.co/api/classes/paladin/levels/3
+            _ if self.index() == "paladin" => vec![(
+                ChannelDivinity,
+                ClassResource::new(u16::from(self.1.level >= 3), Short),
+            )],
+            _ => vec![],
+        };
+
+        for (resource_type, resource) in resources {
+            if resource.max == 0 {
+                self.1.resources.remove(&resource_type);
+                continue;
+            }
+
+            self.1
+                .resources
+                .entry(resource_type)
+                .and_modify(|current| {
+                    current.max = resource.max;
+                    current.used = current.used.min(resource.max);
+                    current.restored_on = resource.restored_on;
+                    current.die = resource.die;
+                })
+                .or_insert(resource);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the indexes of the subclasses available for this class
+    pub async fn get_subclasses(&self) -> Result<Vec<String>, ApiError> {
+        let op = SubclassesQuery::build(SpellcastingAbilityQueryVariables {
+            index: Some(self.index().to_string()),
+        });
+
+        let subclasses = Client::new()
+            .post(GRAPHQL_API_URL.as_str())
+            .run_graphql(op)
+            .await?
+            .data
+            .ok_or(ApiError::Schema)?
+            .class
+            .ok_or(ApiError::Schema)?
+            .subclasses
+            .into_iter()
+            .map(|subclass| subclass.index)
+            .collect();
+
+        Ok(subclasses)
+    }
+
+    /// Returns the indexes of the feats whose ability score prerequisites are met
+    /// See Character::get_available_feats to leave out the feats the character already has
+    pub async fn get_available_feats(&self) -> Result<Vec<String>, ApiError> {
+        get_feats_with_prerequisites_met(&self.1.abilities).await
+    }
+
+    /// Returns the features of the class in the given levels, including the ones of
+    /// the chosen subclass, features of the other subclasses are excluded
+    async fn get_features_in(&self, level: LevelFilter) -> Result<Vec<Feature>, ApiError> {
         let op = LevelFeaturesQuery::build(LevelFeaturesQueryVariables {
             class: Some(StringFilter(self.index().to_string())),
-            level: Some(LevelFilter {
-                gt: Some(self.1.level),
-                lte: Some(new_level),
-                gte: None,
-            }),
+            subclass: None,
+            level: Some(level.clone()),
         });
 
-        let features = Client::new()
+        let mut features: Vec<Feature> = Client::new()
             .post(GRAPHQL_API_URL.as_str())
             .run_graphql(op)
             .await?
             .data
             .ok_or(ApiError::Schema)?
             .features
-            .ok_or(ApiError::Schema)?;
+            .ok_or(ApiError::Schema)?
+            .into_iter()
+            .filter(|feature| feature.subclass.is_none())
+            .collect();
 
+        if let Some(subclass) = &self.1.subclass {
+            let op = LevelFeaturesQuery::build(LevelFeaturesQueryVariables {
+                class: None,
+                subclass: Some(StringFilter(subclass.clone())),
+                level: Some(level),
+            });
+
+            let subclass_features = Client::new()
+                .post(GRAPHQL_API_URL.as_str())
+                .run_graphql(op)
+                .await?
+                .data
+                .ok_or(ApiError::Schema)?
+                .features
+                .ok_or(ApiError::Schema)?;
+
+            features.extend(subclass_features);
+        }
+
+        Ok(features)
+    }
+
+    /// Applies the sheet features and returns the ones that need a choice from the user
+    async fn apply_features(
+        &mut self,
+        features: Vec<Feature>,
+    ) -> Result<Vec<ChoosableCustomLevelFeature>, ApiError> {
         let mut pending_features = vec![];
 
-        features
-            .iter()
-            .filter_map(|feature| CustomLevelFeatureType::identify(feature.index.clone()))
-            .for_each(|feature| match feature {
+        for (level, feature) in features.into_iter().filter_map(|feature| {
+            CustomLevelFeatureType::identify(feature.index).map(|custom| (feature.level, custom))
+        }) {
+            match feature {
                 CustomLevelFeatureType::Passive => {}
+                CustomLevelFeatureType::Choosable(
+                    ChoosableCustomLevelFeature::AbilityScoreImprovement { .. },
+                ) => {
+                    pending_features.push(ChoosableCustomLevelFeature::AbilityScoreImprovement {
+                        level: level.max(0) as u8,
+                        abilities: self.improvable_abilities(),
+                        feats: self.get_available_feats().await?,
+                    });
+                }
                 CustomLevelFeatureType::Choosable(feature) => {
                     pending_features.push(feature);
                 }
+                CustomLevelFeatureType::SubclassChoice => {
+                    if self.1.subclass.is_none() {
+                        pending_features.push(ChoosableCustomLevelFeature::Subclass(
+                            self.get_subclasses().await?,
+                        ));
+                    }
+                }
                 CustomLevelFeatureType::Sheet(feature) => match feature {
                     SheetLevelFeatureType::PrimalChampion => {
                         let mut abilities = self.1.abilities.lock().unwrap();
-                        abilities.strength.score += 4;
-                        abilities.constitution.score += 4;
+                        let abilities = &mut *abilities;
+                        let source = AbilityScoreSource::Feature("primal-champion".to_string());
+                        for ability in [&mut abilities.strength, &mut abilities.constitution] {
+                            ability.remove_modifiers(&source);
+                            if let Some(amount) = (1..=4).rev().find(|amount| {
+                                ability.can_increase(*amount, PRIMAL_CHAMPION_SCORE_MAX)
+                            }) {
+                                ability.add_modifier(
+                                    source.clone(),
+                                    AbilityScoreChange::Bonus(amount as i8),
+                                );
+                            }
+                        }
                     }
                 },
                 Ignored => {}
-            });
-
-        self.1.level = new_level;
+            }
+        }
 
         Ok(pending_features)
     }
 
-    pub async fn get_levels_features(
-        &self,
-        from_level: Option<u8>,
-        passive: bool,
-    ) -> Result<Vec<String>, ApiError> {
+    /// Sets the subclass and returns the choosable features it unlocks up to the current level
+    /// Returns an error if the subclass is not one of the class
+    /// # Arguments
+    /// * `subclass` - Index from https://www.dnd5eapi.co/api/subclasses/
+    pub async fn set_subclass(
+        &mut self,
+        subclass: String,
+    ) -> anyhow::Result<Vec<ChoosableCustomLevelFeature>> {
+        if !self.get_subclasses().await?.contains(&subclass) {
+            bail!("{} is not a subclass of {}", subclass, self.index())
+        }
+
+        self.1.subclass = Some(subclass.clone());
+
         let op = LevelFeaturesQuery::build(LevelFeaturesQueryVariables {
-            class: Some(StringFilter(self.index().to_string())),
+            class: None,
+            subclass: Some(StringFilter(subclass)),
             level: Some(LevelFilter {
-                gte: Some(from_level.unwrap_or(0)),
-                lte: Some(self.1.level),
+                gte: None,
                 gt: None,
+                lte: Some(self.1.level),
             }),
         });
 
@@ -852,6 +1284,52 @@ impl Class {
             .features
             .ok_or(ApiError::Schema)?;
 
+        let pending_features = self.apply_features(features).await?;
+
+        self.update_subclass_spells().await?;
+
+        Ok(pending_features)
+    }
+
+    pub async fn set_level(
+        &mut self,
+        new_level: u8,
+    ) -> Result<Vec<ChoosableCustomLevelFeature>, ApiError> {
+        let features = self
+            .get_features_in(LevelFilter {
+                gt: Some(self.1.level),
+                lte: Some(new_level),
+                gte: None,
+            })
+            .await?;
+
+        let pending_features = self.apply_features(features).await?;
+
+        self.1.pending_hit_points = self
+            .1
+            .pending_hit_points
+            .saturating_add(new_level.saturating_sub(self.1.level));
+        self.1.level = new_level;
+
+        self.update_resources().await?;
+        self.update_subclass_spells().await?;
+
+        Ok(pending_features)
+    }
+
+    pub async fn get_levels_features(
+        &self,
+        from_level: Option<u8>,
+        passive: bool,
+    ) -> Result<Vec<String>, ApiError> {
+        let features = self
+            .get_features_in(LevelFilter {
+                gte: Some(from_level.unwrap_or(0)),
+                lte: Some(self.1.level),
+                gt: None,
+            })
+            .await?;
+
         // First convert features to String objects and filter out non-matching features
         let features: Vec<String> = features
             .into_iter()
@@ -1057,14 +1535,79 @@ impl Class {
         Ok(features)
     }
 
-    pub fn apply_option(&mut self, option: ChoosableCustomLevelFeatureOption) {
+    /// Applies the answers to a choosable feature returned by Class::set_level or
+    /// Class::set_subclass, one option from each list of ChoosableCustomLevelFeature::to_options
+    /// Returns the choosable features unlocked by the answers, e.g. the ones of the chosen subclass
+    /// Returns an error without applying anything if an option is not one of the feature
+    /// The feats are stored in the character, they are chosen with Character::apply_choice
+    pub async fn apply_choice(
+        &mut self,
+        feature: &ChoosableCustomLevelFeature,
+        options: Vec<ChoosableCustomLevelFeatureOption>,
+    ) -> anyhow::Result<Vec<ChoosableCustomLevelFeature>> {
+        if let ChoosableCustomLevelFeature::AbilityScoreImprovement {
+            level, abilities, ..
+        } = feature
+        {
+            match options.as_slice() {
+                // The feats are stored in the character, to check them against every class
+                [ChoosableCustomLevelFeatureOption::Feat(_)] => {
+                    bail!("Use Character::apply_choice to choose a feat")
+                }
+                [first, second] if abilities.contains(first) && abilities.contains(second) => {
+                    self.increase_scores(&options, *level)?
+                }
+                _ => bail!("An ability score improvement is two of its abilities or a single feat"),
+            }
+
+            return Ok(vec![]);
+        }
+
+        let choices = feature.to_options();
+
+        if options.len() != choices.len()
+            || options
+                .iter()
+                .zip(&choices)
+                .any(|(option, choice)| !choice.contains(option))
+        {
+            bail!("The options are not the ones of the feature")
+        }
+
+        if let [ChoosableCustomLevelFeatureOption::Subclass(subclass)] = options.as_slice() {
+            return self.set_subclass(subclass.clone()).await;
+        }
+
+        for option in options {
+            self.apply_option(option)?;
+        }
+
+        if matches!(feature, ChoosableCustomLevelFeature::CircleOfTheLandTerrain) {
+            self.update_subclass_spells().await?;
+        }
+
+        Ok(vec![])
+    }
+
+    /// Applies the chosen option to the class
+    /// Returns an error without applying anything if the option depends on the rest of the
+    /// answer, ability score improvements, feats and subclasses go through Character::apply_choice
+    pub fn apply_option(
+        &mut self,
+        option: ChoosableCustomLevelFeatureOption,
+    ) -> anyhow::Result<()> {
         use ChoosableCustomLevelFeatureOption::*;
 
         match option {
+            // Either two abilities or a feat can be chosen, see Character::apply_choice
             StrengthPlusOne | DexterityPlusOne | ConstitutionPlusOne | IntelligencePlusOne
-            | WisdomPlusOne | CharismaPlusOne => self.increase_score(option),
+            | WisdomPlusOne | CharismaPlusOne | Feat(_) => {
+                bail!("Use Character::apply_choice to apply an ability score improvement")
+            }
             PactOfTheChain | PactOfTheBlade | PactOfTheTome => {
-                println!("Pact of the Chain, Blade or Tome not yet implemented");
+                self.1
+                    .warlock_pact
+                    .replace(option.as_index_str().to_string());
             }
             HuntersPreyGiantKiller | HuntersPreyHordeBreaker | HuntersPreyColossusSlayer => {
                 self.1
@@ -1209,32 +1752,101 @@ impl Class {
                     .sorcerer_dragon_ancestor
                     .replace(option.as_index_str().to_string());
             }
+            // The subclass must be one of the class and adds its features, see Class::set_subclass
+            Subclass(_) => {
+                bail!("Use Class::apply_choice or Class::set_subclass to choose the subclass")
+            }
+            // Class::apply_choice also adds the circle spells of the terrain
+            CircleOfTheLandArctic
+            | CircleOfTheLandCoast
+            | CircleOfTheLandDesert
+            | CircleOfTheLandForest
+            | CircleOfTheLandGrassland
+            | CircleOfTheLandMountain
+            | CircleOfTheLandSwamp
+            | CircleOfTheLandUnderdark => {
+                self.1
+                    .druid_land_terrain
+                    .replace(option.as_index_str().to_string());
+            }
         }
+
+        Ok(())
+    }
+
+    /// Returns the ability score improvement options that would not go over the maximum score
+    pub fn improvable_abilities(&self) -> Vec<ChoosableCustomLevelFeatureOption> {
+        let abilities = self.1.abilities.lock().unwrap();
+
+        ABILITY_SCORE_IMPROVEMENT_OPTIONS
+            .into_iter()
+            .filter(|option| {
+                let ability = match option {
+                    ChoosableCustomLevelFeatureOption::StrengthPlusOne => &abilities.strength,
+                    ChoosableCustomLevelFeatureOption::DexterityPlusOne => &abilities.dexterity,
+                    ChoosableCustomLevelFeatureOption::ConstitutionPlusOne => {
+                        &abilities.constitution
+                    }
+                    ChoosableCustomLevelFeatureOption::IntelligencePlusOne => {
+                        &abilities.intelligence
+                    }
+                    ChoosableCustomLevelFeatureOption::WisdomPlusOne => &abilities.wisdom,
+                    _ => &abilities.charisma,
+                };
+
+                ability.can_increase(1, ABILITY_SCORE_MAX)
+            })
+            .collect()
     }
 
-    fn increase_score(&mut self, option: ChoosableCustomLevelFeatureOption) {
+    /// Adds 1 to the ability of each option, none of them if one would go over the maximum
+    /// # Arguments
+    /// * `level` - Class level that granted the ability score improvement
+    fn increase_scores(
+        &mut self,
+        options: &[ChoosableCustomLevelFeatureOption],
+        level: u8,
+    ) -> anyhow::Result<()> {
+        let indexes = options
+            .iter()
+            .map(|option| match option {
+                ChoosableCustomLevelFeatureOption::StrengthPlusOne => Ok("strength"),
+                ChoosableCustomLevelFeatureOption::DexterityPlusOne => Ok("dexterity"),
+                ChoosableCustomLevelFeatureOption::ConstitutionPlusOne => Ok("constitution"),
+                ChoosableCustomLevelFeatureOption::IntelligencePlusOne => Ok("intelligence"),
+                ChoosableCustomLevelFeatureOption::WisdomPlusOne => Ok("wisdom"),
+                ChoosableCustomLevelFeatureOption::CharismaPlusOne => Ok("charisma"),
+                _ => Err(anyhow::anyhow!(
+                    "The option is not an ability score improvement"
+                )),
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
         let mut abilities = self.1.abilities.lock().unwrap();
-        match option {
-            ChoosableCustomLevelFeatureOption::StrengthPlusOne => {
-                abilities.strength.score += 1;
-            }
-            ChoosableCustomLevelFeatureOption::DexterityPlusOne => {
-                abilities.dexterity.score += 1;
-            }
-            ChoosableCustomLevelFeatureOption::ConstitutionPlusOne => {
-                abilities.constitution.score += 1;
-            }
-            ChoosableCustomLevelFeatureOption::IntelligencePlusOne => {
-                abilities.intelligence.score += 1;
-            }
-            ChoosableCustomLevelFeatureOption::WisdomPlusOne => {
-                abilities.wisdom.score += 1;
+
+        for index in &indexes {
+            let amount = indexes.iter().filter(|other| *other == index).count() as u8;
+
+            if !abilities
+                .get(index)
+                .is_some_and(|ability| ability.can_increase(amount, ABILITY_SCORE_MAX))
+            {
+                bail!("The {} score can't go over {}", index, ABILITY_SCORE_MAX)
             }
-            ChoosableCustomLevelFeatureOption::CharismaPlusOne => {
-                abilities.charisma.score += 1;
+        }
+
+        let source = AbilityScoreSource::AbilityScoreImprovement {
+            class: self.index().to_string(),
+            level,
+        };
+
+        for index in indexes {
+            if let Some(ability) = abilities.get_mut(index) {
+                ability.increase(1, ABILITY_SCORE_MAX, source.clone())?;
             }
-            _ => {}
         }
+
+        Ok(())
     }
 }
 
@@ -1258,3 +1870,53 @@ pub async fn get_spellcasting_slots(
 
     Ok(spellcasting_slots)
 }
+
+pub async fn get_class_specific(index: &str, level: u8) -> Result<Option<ClassSpecific>, ApiError> {
+    let op = ClassSpecificQuery::build(SpellcastingQueryVariables {
+        index: Some(format!("{}-{}", index, level)),
+    });
+
+    let class_specific = Client::new()
+        .post(GRAPHQL_API_URL.as_str())
+        .run_graphql(op)
+        .await?
+        .data
+        .ok_or(ApiError::Schema)?
+        .level
+        .ok_or(ApiError::Schema)?
+        .class_specific;
+
+    Ok(class_specific)
+}
+
+/// Returns the indexes of the feats whose ability score prerequisites are met by the abilities
+pub async fn get_feats_with_prerequisites_met(
+    abilities: &Mutex<Abilities>,
+) -> Result<Vec<String>, ApiError> {
+    let op = FeatsQuery::build(());
+
+    let feats = Client::new()
+        .post(GRAPHQL_API_URL.as_str())
+        .run_graphql(op)
+        .await?
+        .data
+        .ok_or(ApiError::Schema)?
+        .feats
+        .ok_or(ApiError::Schema)?;
+
+    let abilities = abilities.lock().unwrap();
+
+    let feats = feats
+        .into_iter()
+        .filter(|feat| {
+            feat.prerequisites.iter().all(|prerequisite| {
+                abilities
+                    .get(&prerequisite.ability_score.index)
+                    .is_some_and(|ability| ability.total() as i32 >= prerequisite.minimum_score)
+            })
+        })
+        .map(|feat| feat.index)
+        .collect();
+
+    Ok(feats)
+}
@@ -0,0 +1,380 @@
+use super::shared::{ApiError, schema};
+use crate::armor::{Armor, ArmorCategory};
+use crate::equipment::{Item, ItemDetails, ItemState, Weapon, WeaponRange};
+use crate::magic_items::{MagicItem, Rarity};
+use crate::money::{Cost, Currency};
+use crate::{Character, GRAPHQL_API_URL};
+use cynic::QueryBuilder;
+use cynic::http::ReqwestExt;
+use reqwest::Client;
+
+#[derive(cynic::QueryVariables, Debug)]
+pub struct EquipmentQueryVariables {
+    pub index: Option<String>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Query", variables = "EquipmentQueryVariables")]
+pub struct EquipmentQuery {
+    #[arguments(index: $index)]
+    pub equipment: Option<Equipment>,
+}
+
+#[derive(cynic::InlineFragments, Debug)]
+#[cynic(graphql_type = "IEquipment")]
+pub enum Equipment {
+    Armor(ArmorStats),
+    Weapon(WeaponStats),
+    #[cynic(fallback)]
+    Other(EquipmentStats),
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "IEquipment")]
+pub struct EquipmentStats {
+    pub index: String,
+    pub name: String,
+    pub cost: ApiCost,
+    pub weight: Option<f64>,
+    #[cynic(rename = "equipment_category")]
+    pub equipment_category: EquipmentCategoryIndex,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Armor")]
+pub struct ArmorStats {
+    pub index: String,
+    pub name: String,
+    pub cost: ApiCost,
+    pub weight: Option<f64>,
+    #[cynic(rename = "equipment_category")]
+    pub equipment_category: EquipmentCategoryIndex,
+    #[cynic(rename = "armor_category")]
+    pub armor_category: EquipmentCategoryIndex,
+    #[cynic(rename = "str_minimum")]
+    pub str_minimum: i32,
+    #[cynic(rename = "stealth_disadvantage")]
+    pub stealth_disadvantage: bool,
+    #[cynic(rename = "armor_class")]
+    pub armor_class: ArmorClassStats,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "ArmorClass")]
+pub struct ArmorClassStats {
+    pub base: i32,
+    #[cynic(rename = "dex_bonus")]
+    pub dex_bonus: bool,
+    #[cynic(rename = "max_bonus")]
+    pub max_bonus: Option<i32>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Weapon")]
+pub struct WeaponStats {
+    pub index: String,
+    pub name: String,
+    pub cost: ApiCost,
+    pub weight: Option<f64>,
+    #[cynic(rename = "equipment_category")]
+    pub equipment_category: EquipmentCategoryIndex,
+    #[cynic(rename = "weapon_category")]
+    pub weapon_category: EquipmentCategoryIndex,
+    #[cynic(rename = "weapon_range")]
+    pub weapon_range: ApiWeaponRange,
+    pub damage: Option<ApiDamage>,
+    #[cynic(rename = "two_handed_damage")]
+    pub two_handed_damage: Option<ApiDamage>,
+    pub range: ApiRange,
+    #[cynic(rename = "throw_range")]
+    pub throw_range: Option<ApiRange>,
+    pub properties: Vec<WeaponPropertyIndex>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Damage")]
+pub struct ApiDamage {
+    #[cynic(rename = "damage_dice")]
+    pub damage_dice: String,
+    #[cynic(rename = "damage_type")]
+    pub damage_type: DamageTypeIndex,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "DamageType")]
+pub struct DamageTypeIndex {
+    pub index: String,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Range")]
+pub struct ApiRange {
+    pub normal: i32,
+    pub long: Option<i32>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "WeaponProperty")]
+pub struct WeaponPropertyIndex {
+    pub index: String,
+}
+
+#[derive(cynic::Enum, Clone, Copy, Debug)]
+#[cynic(graphql_type = "WeaponRange")]
+pub enum ApiWeaponRange {
+    Melee,
+    Ranged,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Cost")]
+pub struct ApiCost {
+    pub quantity: i32,
+    pub unit: ApiCurrency,
+}
+
+#[derive(cynic::Enum, Clone, Copy, Debug)]
+#[cynic(graphql_type = "Currency")]
+pub enum ApiCurrency {
+    Cp,
+    Sp,
+    Gp,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "EquipmentCategory")]
+pub struct EquipmentCategoryIndex {
+    pub index: String,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Query", variables = "EquipmentQueryVariables")]
+pub struct MagicItemQuery {
+    #[arguments(index: $index)]
+    #[cynic(rename = "magicItem")]
+    pub magic_item: Option<ApiMagicItem>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "MagicItem")]
+pub struct ApiMagicItem {
+    pub index: String,
+    pub name: String,
+    pub desc: Vec<String>,
+    pub rarity: MagicItemRarity,
+    #[cynic(rename = "equipment_category")]
+    pub equipment_category: EquipmentCategoryIndex,
+}
+
+#[derive(cynic::Enum, Clone, Copy, Debug)]
+pub enum MagicItemRarity {
+    Varies,
+    Common,
+    Uncommon,
+    Rare,
+    VeryRare,
+    Legendary,
+    Artifact,
+}
+
+impl From<ApiCost> for Cost {
+    fn from(cost: ApiCost) -> Self {
+        Cost {
+            quantity: cost.quantity.max(0) as u32,
+            unit: match cost.unit {
+                ApiCurrency::Cp => Currency::Cp,
+                ApiCurrency::Sp => Currency::Sp,
+                ApiCurrency::Gp => Currency::Gp,
+            },
+        }
+    }
+}
+
+impl TryFrom<Equipment> for Item {
+    type Error = ApiError;
+
+    fn try_from(equipment: Equipment) -> Result<Self, Self::Error> {
+        let (base, details) = match equipment {
+            Equipment::Armor(armor) => {
+                let category = match armor.armor_category.index.as_str() {
+                    "light-armor" => ArmorCategory::Light,
+                    "medium-armor" => ArmorCategory::Medium,
+                    "heavy-armor" => ArmorCategory::Heavy,
+                    "shields" => ArmorCategory::Shield,
+                    _ => return Err(ApiError::Schema),
+                };
+
+                let details = ItemDetails::Armor(Armor {
+                    index: armor.index.clone(),
+                    category,
+                    base: armor.armor_class.base as u8,
+                    dex_bonus: armor.armor_class.dex_bonus,
+                    max_dex_bonus: armor.armor_class.max_bonus.map(|max| max as u8),
+                    str_minimum: armor.str_minimum as u8,
+                    stealth_disadvantage: armor.stealth_disadvantage,
+                    magic_bonus: 0,
+                });
+
+                let base = EquipmentStats {
+                    index: armor.index,
+                    name: armor.name,
+                    cost: armor.cost,
+                    weight: armor.weight,
+                    equipment_category: armor.equipment_category,
+                };
+
+                (base, details)
+            }
+            Equipment::Weapon(weapon) => {
+                let details = ItemDetails::Weapon(Weapon {
+                    category: weapon.weapon_category.index,
+                    range: match weapon.weapon_range {
+                        ApiWeaponRange::Melee => WeaponRange::Melee,
+                        ApiWeaponRange::Ranged => WeaponRange::Ranged,
+                    },
+                    damage_dice: weapon
+                        .damage
+                        .as_ref()
+                        .map(|damage| damage.damage_dice.clone()),
+                    damage_type: weapon.damage.map(|damage| damage.damage_type.index),
+                    two_handed_damage_dice: weapon
+                        .two_handed_damage
+                        .map(|damage| damage.damage_dice),
+                    normal_range: weapon.range.normal as u16,
+                    long_range: weapon.range.long.map(|long| long as u16),
+                    throw_normal_range: weapon.throw_range.as_ref().map(|r| r.normal as u16),
+                    throw_long_range: weapon
+                        .throw_range
+                        .and_then(|r| r.long)
+                        .map(|long| long as u16),
+                    properties: weapon
+                        .properties
+                        .into_iter()
+                        .map(|property| property.index)
+                        .collect(),
+                });
+
+                let base = EquipmentStats {
+                    index: weapon.index,
+                    name: weapon.name,
+                    cost: weapon.cost,
+                    weight: weapon.weight,
+                    equipment_category: weapon.equipment_category,
+                };
+
+                (base, details)
+            }
+            Equipment::Other(base) => (base, ItemDetails::Other),
+        };
+
+        Ok(Item {
+            index: base.index,
+            name: base.name,
+            quantity: 1,
+            weight: base.weight.unwrap_or_default() as f32,
+            cost: Some(base.cost.into()),
+            category: base.equipment_category.index,
+            state: ItemState::Carried,
+            details,
+            magic: None,
+        })
+    }
+}
+
+impl From<MagicItemRarity> for Rarity {
+    fn from(rarity: MagicItemRarity) -> Self {
+        match rarity {
+            MagicItemRarity::Varies => Rarity::Varies,
+            MagicItemRarity::Common => Rarity::Common,
+            MagicItemRarity::Uncommon => Rarity::Uncommon,
+            MagicItemRarity::Rare => Rarity::Rare,
+            MagicItemRarity::VeryRare => Rarity::VeryRare,
+            MagicItemRarity::Legendary => Rarity::Legendary,
+            MagicItemRarity::Artifact => Rarity::Artifact,
+        }
+    }
+}
+
+/// The api has no effects for magic items, they must be added to MagicItem::effects
+impl From<ApiMagicItem> for Item {
+    fn from(magic_item: ApiMagicItem) -> Self {
+        // e.g. "Wondrous item, uncommon (requires attunement)"
+        let requires_attunement = magic_item
+            .desc
+            .iter()
+            .any(|desc| desc.contains("requires attunement"));
+
+        Item {
+            index: magic_item.index,
+            category: magic_item.equipment_category.index,
+            magic: Some(MagicItem {
+                rarity: magic_item.rarity.into(),
+                requires_attunement,
+                ..Default::default()
+            }),
+            ..Item::custom(magic_item.name, 1)
+        }
+    }
+}
+
+/// Fetches the item with its weight, cost and weapon or armor stats
+/// # Arguments
+/// * `index` - Index from https://www.dnd5eapi.co/api/equipment/
+pub async fn get_item(index: &str) -> Result<Item, ApiError> {
+    let op = EquipmentQuery::build(EquipmentQueryVariables {
+        index: Some(index.to_string()),
+    });
+
+    Client::new()
+        .post(GRAPHQL_API_URL.as_str())
+        .run_graphql(op)
+        .await?
+        .data
+        .ok_or(ApiError::Schema)?
+        .equipment
+        .ok_or(ApiError::Schema)?
+        .try_into()
+}
+
+/// Fetches the magic item with its rarity and whether it requires attunement
+/// # Arguments
+/// * `index` - Index from https://www.dnd5eapi.co/api/magic-items/
+pub async fn get_magic_item(index: &str) -> Result<Item, ApiError> {
+    let op = MagicItemQuery::build(EquipmentQueryVariables {
+        index: Some(index.to_string()),
+    });
+
+    Ok(Client::new()
+        .post(GRAPHQL_API_URL.as_str())
+        .run_graphql(op)
+        .await?
+        .data
+        .ok_or(ApiError::Schema)?
+        .magic_item
+        .ok_or(ApiError::Schema)?
+        .into())
+}
+
+impl Character {
+    /// Adds the magic item to the carried items of the inventory
+    /// # Arguments
+    /// * `index` - Index from https://www.dnd5eapi.co/api/magic-items/
+    pub async fn add_magic_item(&mut self, index: &str) -> Result<(), ApiError> {
+        self.insert_item(get_magic_item(index).await?);
+
+        Ok(())
+    }
+
+    /// Adds the items to the carried items of the inventory
+    /// # Arguments
+    /// * `index` - Index from https://www.dnd5eapi.co/api/equipment/
+    /// * `quantity` - Number of items to add
+    pub async fn add_equipment(&mut self, index: &str, quantity: u16) -> Result<(), ApiError> {
+        let mut item = get_item(index).await?;
+        item.quantity = quantity;
+
+        self.insert_item(item);
+
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
 pub mod classes;
+pub mod equipment;
 pub mod shared;
 pub mod spells;
+pub mod starting_equipment;
@@ -2,7 +2,11 @@ use cynic::http::CynicReqwestError;
 use std::collections::HashMap;
 
 use crate::Character;
-use crate::api::classes::LevelSpellcasting;
+use crate::api::classes::{
+    ChoosableCustomLevelFeature, ChoosableCustomLevelFeatureOption, LevelSpellcasting,
+    get_feats_with_prerequisites_met,
+};
+use crate::classes::UsableSlots;
 
 #[derive(Debug, thiserror::Error)]
 pub enum ApiError {
@@ -27,6 +31,7 @@ pub enum CheckError {
 
 mod race_query {
     use super::schema;
+    use crate::abilities::{ABILITY_NAMES, AbilityScoreChange, AbilityScoreSource};
     use crate::api::shared::ApiError;
     use crate::{Character, GRAPHQL_API_URL};
     use cynic::QueryBuilder;
@@ -51,7 +56,107 @@ mod race_query {
         pub speed: i32,
     }
 
+    #[derive(cynic::QueryVariables, Debug)]
+    struct AbilityBonusesQueryVariables {
+        pub race: String,
+        pub subrace: String,
+    }
+
+    #[derive(cynic::QueryFragment, Debug)]
+    #[cynic(graphql_type = "Query", variables = "AbilityBonusesQueryVariables")]
+    struct AbilityBonusesQuery {
+        #[arguments(index: $race)]
+        pub race: Option<RaceAbilityBonuses>,
+        #[arguments(index: $subrace)]
+        pub subrace: Option<SubraceAbilityBonuses>,
+    }
+
+    #[derive(cynic::QueryFragment, Debug)]
+    #[cynic(graphql_type = "Race")]
+    struct RaceAbilityBonuses {
+        #[cynic(rename = "ability_bonuses")]
+        pub ability_bonuses: Vec<AbilityBonus>,
+    }
+
+    #[derive(cynic::QueryFragment, Debug)]
+    #[cynic(graphql_type = "Subrace")]
+    struct SubraceAbilityBonuses {
+        #[cynic(rename = "ability_bonuses")]
+        pub ability_bonuses: Vec<AbilityBonus>,
+    }
+
+    #[derive(cynic::QueryFragment, Debug)]
+    struct AbilityBonus {
+        #[cynic(rename = "ability_score")]
+        pub ability_score: AbilityScoreIndex,
+        pub bonus: i32,
+    }
+
+    #[derive(cynic::QueryFragment, Debug)]
+    #[cynic(graphql_type = "AbilityScore")]
+    struct AbilityScoreIndex {
+        pub index: String,
+    }
+
     impl Character {
+        /// Sets the race and the subrace, replacing the ability score bonuses of the previous ones
+        /// Character::new only stores the indexes, call this method to apply their bonuses
+        /// # Arguments
+        /// * `race_index` - Index from https://www.dnd5eapi.co/api/races/
+        /// * `subrace_index` - Index from https://www.dnd5eapi.co/api/subraces/, ignored if unknown
+        pub async fn set_race(
+            &mut self,
+            race_index: String,
+            subrace_index: String,
+        ) -> Result<(), ApiError> {
+            let op = AbilityBonusesQuery::build(AbilityBonusesQueryVariables {
+                race: race_index.clone(),
+                subrace: subrace_index.clone(),
+            });
+
+            let data = Client::new()
+                .post(GRAPHQL_API_URL.as_str())
+                .run_graphql(op)
+                .await?
+                .data
+                .ok_or(ApiError::Schema)?;
+
+            let race_bonuses = data
+                .race
+                .ok_or(ApiError::Schema)?
+                .ability_bonuses
+                .into_iter()
+                .map(|bonus| (AbilityScoreSource::Race(race_index.clone()), bonus));
+            let subrace_bonuses = data
+                .subrace
+                .into_iter()
+                .flat_map(|subrace| subrace.ability_bonuses)
+                .map(|bonus| (AbilityScoreSource::Race(subrace_index.clone()), bonus));
+
+            {
+                let mut abilities = self.abilities_score.lock().unwrap();
+
+                for ability in ABILITY_NAMES {
+                    if let Some(ability) = abilities.get_mut(ability) {
+                        ability.modifiers.retain(|modifier| {
+                            !matches!(modifier.source, AbilityScoreSource::Race(_))
+                        });
+                    }
+                }
+
+                for (source, bonus) in race_bonuses.chain(subrace_bonuses) {
+                    if let Some(ability) = abilities.get_mut(&bonus.ability_score.index) {
+                        ability.add_modifier(source, AbilityScoreChange::Bonus(bonus.bonus as i8));
+                    }
+                }
+            }
+
+            self.race_index = race_index;
+            self.subrace_index = subrace_index;
+
+            Ok(())
+        }
+
         pub async fn get_base_speed(&self) -> Result<i32, ApiError> {
             let op = SpeedQuery::build(SpeedQueryVariables {
                 index: self.race_index.clone(),
@@ -69,6 +174,11 @@ mod race_query {
 
             Ok(speed)
         }
+
+        /// Returns the speed of the race reduced by the encumbrance and the worn armor
+        pub async fn get_speed(&self) -> Result<i32, ApiError> {
+            Ok(self.effective_speed(self.get_base_speed().await?))
+        }
     }
 }
 
@@ -101,6 +211,7 @@ impl Character {
 
         let spellcasting_slots = self.get_spellcasting_slots().await?;
         let features = self.get_features(true).await?;
+        let speed = self.get_speed().await?;
 
         let mut character = json!(self);
 
@@ -109,17 +220,129 @@ impl Character {
         }
 
         character["max_hp"] = json!(self.max_hp());
+        character["initiative"] = json!(self.initiative());
+        character["passive_perception"] = json!(self.passive_perception());
+        character["passive_investigation"] = json!(self.passive_investigation());
+        character["passive_insight"] = json!(self.passive_insight());
+        character["speed"] = json!(speed);
+        character["attacks_per_action"] = json!(self.attacks_per_action());
+
+        let proficiency_bonus = self.proficiency_bonus();
+        let spell_save_dc: HashMap<&String, u8> = self
+            .classes
+            .0
+            .iter()
+            .filter_map(|(index, class)| Some((index, class.spell_save_dc(proficiency_bonus)?)))
+            .collect();
+        let spell_attack_bonus: HashMap<&String, i8> = self
+            .classes
+            .0
+            .iter()
+            .filter_map(|(index, class)| {
+                Some((index, class.spell_attack_bonus(proficiency_bonus)?))
+            })
+            .collect();
+
+        if !spell_save_dc.is_empty() {
+            character["spell_save_dc"] = json!(spell_save_dc);
+            character["spell_attack_bonus"] = json!(spell_attack_bonus);
+        }
 
         if !features.is_empty() {
             character["features"] = json!(features);
         }
 
+        let attacks = self.attacks();
+        if !attacks.is_empty() {
+            character["attacks"] = json!(attacks);
+        }
+
         Ok(character)
     }
 
+    /// Returns the indexes of the feats whose ability score prerequisites are met and that the
+    /// character doesn't have yet, from any class
+    pub async fn get_available_feats(&self) -> Result<Vec<String>, ApiError> {
+        let feats = get_feats_with_prerequisites_met(&self.abilities_score).await?;
+
+        Ok(feats
+            .into_iter()
+            .filter(|feat| !self.has_feat(feat))
+            .collect())
+    }
+
+    /// Applies the answers to a choosable feature of the class, see Class::apply_choice
+    /// A feat chosen instead of an ability score improvement is stored in the character,
+    /// it is rejected if the character already has it from any class
+    /// # Arguments
+    /// * `class` - Index from https://www.dnd5eapi.co/api/classes/ of the class that gained the feature
+    pub async fn apply_choice(
+        &mut self,
+        class: &str,
+        feature: &ChoosableCustomLevelFeature,
+        options: Vec<ChoosableCustomLevelFeatureOption>,
+    ) -> anyhow::Result<Vec<ChoosableCustomLevelFeature>> {
+        let Some(class) = self.classes.0.get_mut(class) else {
+            anyhow::bail!("The character doesn't have the class {}", class)
+        };
+
+        let (
+            ChoosableCustomLevelFeature::AbilityScoreImprovement { feats, .. },
+            [ChoosableCustomLevelFeatureOption::Feat(feat)],
+        ) = (feature, options.as_slice())
+        else {
+            return class.apply_choice(feature, options).await;
+        };
+
+        if !feats.contains(feat) {
+            anyhow::bail!("The feat {} is not one of the feature", feat)
+        }
+        if self.has_feat(feat) {
+            anyhow::bail!("The character already has the feat {}", feat)
+        }
+
+        // The effects of the feats are applied where they are used, e.g. tough in max_hp
+        self.feats.push(feat.clone());
+
+        Ok(vec![])
+    }
+
+    /// Call this method after a short rest, recovering the given spell slots with the
+    /// Arcane Recovery of the wizard class, see Character::short_rest_recovering_slots
+    pub async fn short_rest_with_arcane_recovery(&mut self, slots: &[u8]) -> anyhow::Result<()> {
+        let Some(wizard) = self.classes.0.get("wizard") else {
+            anyhow::bail!("The character doesn't have the wizard class")
+        };
+
+        let max_slots: UsableSlots = wizard
+            .get_spellcasting_slots()
+            .await?
+            .map(Into::into)
+            .unwrap_or_default();
+
+        self.short_rest_recovering_slots(slots, &max_slots)
+    }
+
     /// Call this method every day to reset daily vars
-    pub async fn new_day(&mut self) {
+    /// Returns an error if the level data of a class can't be fetched, before the hit points
+    /// and the exhaustion are restored
+    pub async fn new_day(&mut self) -> Result<(), ApiError> {
+        // A dead character doesn't rest, a lower exhaustion would bring it back to life
+        if self.is_dead() {
+            return Ok(());
+        }
+
+        self.classes.new_day().await?;
+
+        // The long rest reduces the exhaustion before the hit point maximum is restored
+        self.remove_exhaustion(1);
+        self.end_rest_conditions(crate::classes::RestType::Long);
+
         self.hp = self.max_hp();
-        self.classes.new_day().await;
+        self.death_saves = Default::default();
+        self.temporary_hp = 0;
+        self.recharge_magic_items(crate::magic_items::Recharge::Dawn);
+
+        Ok(())
     }
 }
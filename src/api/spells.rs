@@ -1,19 +1,30 @@
+use super::equipment::DamageTypeIndex;
 use super::shared::{schema, ApiError};
 use crate::classes::{Class, ClassSpellCasting};
-use crate::GRAPHQL_API_URL;
+use crate::spells::{
+    AreaOfEffect, AreaOfEffectShape, DcSuccess, SpellAttackType, SpellComponent, SpellDc,
+    SpellDetails, SpellFilter,
+};
+use crate::{Character, GRAPHQL_API_URL};
 use cynic::http::ReqwestExt;
 use cynic::QueryBuilder;
 use reqwest::Client;
 
+/// Maximum number of spells returned by the spells queries, the API has no option to return
+/// all of them and only returns 100 by default
+pub const SPELLS_QUERY_LIMIT: i32 = 999_999_999;
+
 #[derive(cynic::QueryVariables, Debug)]
 pub struct SpellsQueryVariables {
     pub class: Option<StringFilter>,
+    /// See SPELLS_QUERY_LIMIT
+    pub limit: i32,
 }
 
 #[derive(cynic::QueryFragment, Debug)]
 #[cynic(graphql_type = "Query", variables = "SpellsQueryVariables")]
 pub struct SpellsQuery {
-    #[arguments(class: $class, limit: 999999999)]
+    #[arguments(class: $class, limit: $limit)]
     pub spells: Option<Vec<Spell>>,
 }
 
@@ -23,11 +34,465 @@ pub struct SpellsQuery {
 pub struct Spell {
     pub index: String,
     pub level: i32,
+    /// If the spell can be cast as a ritual, see Class::can_cast_as_ritual
+    pub ritual: bool,
 }
 
 #[derive(cynic::Scalar, Debug, Clone)]
 pub struct StringFilter(pub String);
 
+/// List of the accepted values
+#[derive(cynic::Scalar, Debug, Clone)]
+pub struct IntFilter(pub Vec<i32>);
+
+/// MELEE or RANGED
+#[derive(cynic::Scalar, Debug, Clone)]
+pub struct SpellAttackTypeFilter(pub String);
+
+/// SPHERE, CUBE, CYLINDER, LINE or CONE
+#[derive(cynic::Scalar, Debug, Clone)]
+pub struct AreaOfEffectTypeFilter(pub String);
+
+#[derive(cynic::InputObject, Debug, Clone)]
+pub struct AreaOfEffectFilter {
+    #[cynic(rename = "type")]
+    pub shape: Option<AreaOfEffectTypeFilter>,
+    pub size: Option<IntFilter>,
+}
+
+#[derive(cynic::QueryVariables, Debug, Default)]
+pub struct SpellDetailsQueryVariables {
+    pub index: Option<String>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Query", variables = "SpellDetailsQueryVariables")]
+pub struct SpellDetailsQuery {
+    #[arguments(index: $index)]
+    pub spell: Option<ApiSpellDetails>,
+}
+
+#[derive(cynic::QueryVariables, Debug)]
+pub struct FilteredSpellsQueryVariables {
+    pub class: Option<StringFilter>,
+    pub subclass: Option<StringFilter>,
+    pub school: Option<StringFilter>,
+    pub level: Option<IntFilter>,
+    pub concentration: Option<bool>,
+    pub ritual: Option<bool>,
+    pub attack_type: Option<SpellAttackTypeFilter>,
+    pub casting_time: Option<StringFilter>,
+    pub area_of_effect: Option<AreaOfEffectFilter>,
+    pub damage_type: Option<StringFilter>,
+    pub dc_type: Option<StringFilter>,
+    pub range: Option<StringFilter>,
+    pub name: Option<String>,
+    /// See SPELLS_QUERY_LIMIT
+    pub limit: i32,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Query", variables = "FilteredSpellsQueryVariables")]
+pub struct FilteredSpellsQuery {
+    #[arguments(
+        class: $class,
+        subclass: $subclass,
+        school: $school,
+        level: $level,
+        concentration: $concentration,
+        ritual: $ritual,
+        attack_type: $attack_type,
+        casting_time: $casting_time,
+        area_of_effect: $area_of_effect,
+        damage_type: $damage_type,
+        dc_type: $dc_type,
+        range: $range,
+        name: $name,
+        limit: $limit
+    )]
+    pub spells: Option<Vec<ApiSpellDetails>>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Spell")]
+pub struct ApiSpellDetails {
+    pub index: String,
+    pub name: String,
+    pub level: i32,
+    pub school: MagicSchoolIndex,
+    #[cynic(rename = "casting_time")]
+    pub casting_time: String,
+    pub range: String,
+    pub components: Option<Vec<Option<ApiSpellComponent>>>,
+    pub material: Option<String>,
+    pub duration: String,
+    pub concentration: bool,
+    pub ritual: bool,
+    #[cynic(rename = "attack_type")]
+    pub attack_type: Option<ApiSpellAttackType>,
+    pub damage: Option<ApiSpellDamage>,
+    #[cynic(rename = "heal_at_slot_level")]
+    pub heal_at_slot_level: Option<Vec<HealingAtLevel>>,
+    pub dc: Option<ApiSpellDc>,
+    #[cynic(rename = "area_of_effect")]
+    pub area_of_effect: Option<ApiAreaOfEffect>,
+    pub classes: Vec<ClassIndex>,
+    pub subclasses: Vec<SubclassIndex>,
+    pub desc: Vec<String>,
+    #[cynic(rename = "higher_level")]
+    pub higher_level: Option<Vec<String>>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "MagicSchool")]
+pub struct MagicSchoolIndex {
+    pub index: String,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Class")]
+pub struct ClassIndex {
+    pub index: String,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Subclass")]
+pub struct SubclassIndex {
+    pub index: String,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "AbilityScore")]
+pub struct AbilityScoreIndex {
+    pub index: String,
+}
+
+#[derive(cynic::Enum, Clone, Copy, Debug)]
+#[cynic(graphql_type = "SpellComponent")]
+pub enum ApiSpellComponent {
+    V,
+    S,
+    M,
+}
+
+#[derive(cynic::Enum, Clone, Copy, Debug)]
+#[cynic(graphql_type = "SpellAttackType")]
+pub enum ApiSpellAttackType {
+    Melee,
+    Ranged,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "SpellDamage")]
+pub struct ApiSpellDamage {
+    #[cynic(rename = "damage_at_slot_level")]
+    pub damage_at_slot_level: Option<Vec<DamageAtLevel>>,
+    #[cynic(rename = "damage_at_character_level")]
+    pub damage_at_character_level: Option<Vec<DamageAtLevel>>,
+    #[cynic(rename = "damage_type")]
+    pub damage_type: Option<DamageTypeIndex>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+pub struct DamageAtLevel {
+    pub level: i32,
+    pub damage: String,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+pub struct HealingAtLevel {
+    pub level: i32,
+    pub healing: String,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "SpellDc")]
+pub struct ApiSpellDc {
+    pub success: ApiDcSuccess,
+    #[cynic(rename = "type")]
+    pub ability: AbilityScoreIndex,
+    pub desc: Option<String>,
+}
+
+#[derive(cynic::Enum, Clone, Copy, Debug)]
+#[cynic(graphql_type = "DcSuccess")]
+pub enum ApiDcSuccess {
+    None,
+    Half,
+    Other,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "AreaOfEffect")]
+pub struct ApiAreaOfEffect {
+    #[cynic(rename = "type")]
+    pub shape: ApiAreaOfEffectType,
+    pub size: i32,
+}
+
+#[derive(cynic::Enum, Clone, Copy, Debug)]
+#[cynic(graphql_type = "AreaOfEffectType")]
+pub enum ApiAreaOfEffectType {
+    Sphere,
+    Cube,
+    Cylinder,
+    Line,
+    Cone,
+}
+
+impl From<ApiSpellDetails> for SpellDetails {
+    fn from(spell: ApiSpellDetails) -> Self {
+        let (damage_type, damage_at_slot_level, damage_at_character_level) = match spell.damage {
+            Some(damage) => (
+                damage.damage_type.map(|damage_type| damage_type.index),
+                damage.damage_at_slot_level.unwrap_or_default(),
+                damage.damage_at_character_level.unwrap_or_default(),
+            ),
+            None => (None, vec![], vec![]),
+        };
+
+        let by_level = |damage: Vec<DamageAtLevel>| {
+            damage
+                .into_iter()
+                .map(|damage| (damage.level.max(0) as u8, damage.damage))
+                .collect()
+        };
+
+        SpellDetails {
+            index: spell.index,
+            name: spell.name,
+            level: spell.level.max(0) as u8,
+            school: spell.school.index,
+            casting_time: spell.casting_time,
+            range: spell.range,
+            components: spell
+                .components
+                .unwrap_or_default()
+                .into_iter()
+                .flatten()
+                .map(|component| match component {
+                    ApiSpellComponent::V => SpellComponent::Verbal,
+                    ApiSpellComponent::S => SpellComponent::Somatic,
+                    ApiSpellComponent::M => SpellComponent::Material,
+                })
+                .collect(),
+            material: spell.material,
+            duration: spell.duration,
+            concentration: spell.concentration,
+            ritual: spell.ritual,
+            attack_type: spell.attack_type.map(|attack_type| match attack_type {
+                ApiSpellAttackType::Melee => SpellAttackType::Melee,
+                ApiSpellAttackType::Ranged => SpellAttackType::Ranged,
+            }),
+            damage_type,
+            damage_at_slot_level: by_level(damage_at_slot_level),
+            damage_at_character_level: by_level(damage_at_character_level),
+            heal_at_slot_level: spell
+                .heal_at_slot_level
+                .unwrap_or_default()
+                .into_iter()
+                .map(|heal| (heal.level.max(0) as u8, heal.healing))
+                .collect(),
+            dc: spell.dc.map(|dc| SpellDc {
+                ability: dc.ability.index,
+                success: match dc.success {
+                    ApiDcSuccess::None => DcSuccess::None,
+                    ApiDcSuccess::Half => DcSuccess::Half,
+                    ApiDcSuccess::Other => DcSuccess::Other,
+                },
+                desc: dc.desc,
+            }),
+            area_of_effect: spell.area_of_effect.map(|area| AreaOfEffect {
+                shape: match area.shape {
+                    ApiAreaOfEffectType::Sphere => AreaOfEffectShape::Sphere,
+                    ApiAreaOfEffectType::Cube => AreaOfEffectShape::Cube,
+                    ApiAreaOfEffectType::Cylinder => AreaOfEffectShape::Cylinder,
+                    ApiAreaOfEffectType::Line => AreaOfEffectShape::Line,
+                    ApiAreaOfEffectType::Cone => AreaOfEffectShape::Cone,
+                },
+                size: area.size.max(0) as u16,
+            }),
+            classes: spell.classes.into_iter().map(|class| class.index).collect(),
+            subclasses: spell
+                .subclasses
+                .into_iter()
+                .map(|subclass| subclass.index)
+                .collect(),
+            desc: spell.desc,
+            higher_level: spell.higher_level.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<&SpellFilter> for FilteredSpellsQueryVariables {
+    fn from(filter: &SpellFilter) -> Self {
+        let string_filter = |value: &Option<String>| value.clone().map(StringFilter);
+
+        FilteredSpellsQueryVariables {
+            class: string_filter(&filter.class),
+            subclass: string_filter(&filter.subclass),
+            school: string_filter(&filter.school),
+            level: (!filter.levels.is_empty())
+                .then(|| IntFilter(filter.levels.iter().map(|&level| level as i32).collect())),
+            concentration: filter.concentration,
+            ritual: filter.ritual,
+            attack_type: filter.attack_type.map(|attack_type| {
+                SpellAttackTypeFilter(
+                    match attack_type {
+                        SpellAttackType::Melee => "MELEE",
+                        SpellAttackType::Ranged => "RANGED",
+                    }
+                    .to_string(),
+                )
+            }),
+            casting_time: string_filter(&filter.casting_time),
+            area_of_effect: filter.area_of_effect.map(|shape| AreaOfEffectFilter {
+                shape: Some(AreaOfEffectTypeFilter(
+                    match shape {
+                        AreaOfEffectShape::Sphere => "SPHERE",
+                        AreaOfEffectShape::Cube => "CUBE",
+                        AreaOfEffectShape::Cylinder => "CYLINDER",
+                        AreaOfEffectShape::Line => "LINE",
+                        AreaOfEffectShape::Cone => "CONE",
+                    }
+                    .to_string(),
+                )),
+                size: None,
+            }),
+            damage_type: string_filter(&filter.damage_type),
+            dc_type: string_filter(&filter.dc_type),
+            range: string_filter(&filter.range),
+            name: filter.name.clone(),
+            limit: SPELLS_QUERY_LIMIT,
+        }
+    }
+}
+
+/// Fetches all the details of a spell
+/// # Arguments
+/// * `index` - Index from https://www.dnd5eapi.co/api/spells/
+pub async fn get_spell_details(index: &str) -> Result<SpellDetails, ApiError> {
+    let op = SpellDetailsQuery::build(SpellDetailsQueryVariables {
+        index: Some(index.to_string()),
+    });
+
+    let spell = Client::new()
+        .post(GRAPHQL_API_URL.as_str())
+        .run_graphql(op)
+        .await?
+        .data
+        .ok_or(ApiError::Schema)?
+        .spell
+        .ok_or(ApiError::Schema)?;
+
+    Ok(spell.into())
+}
+
+impl Character {
+    /// Fetches the cantrip and returns its damage when cast by the character,
+    /// see Character::cantrip_damage
+    /// # Arguments
+    /// * `index` - Index from https://www.dnd5eapi.co/api/spells/
+    pub async fn get_cantrip_damage(&self, index: &str) -> Result<Option<String>, ApiError> {
+        let spell = get_spell_details(index).await?;
+
+        Ok(self.cantrip_damage(&spell).map(str::to_string))
+    }
+}
+
+/// Fetches the details of the spells matching the filter
+pub async fn get_filtered_spells(filter: &SpellFilter) -> Result<Vec<SpellDetails>, ApiError> {
+    let op = FilteredSpellsQuery::build(FilteredSpellsQueryVariables::from(filter));
+
+    let spells = Client::new()
+        .post(GRAPHQL_API_URL.as_str())
+        .run_graphql(op)
+        .await?
+        .data
+        .ok_or(ApiError::Schema)?
+        .spells
+        .ok_or(ApiError::Schema)?;
+
+    Ok(spells.into_iter().map(SpellDetails::from).collect())
+}
+
+#[derive(cynic::QueryVariables, Debug)]
+pub struct SubclassSpellsQueryVariables {
+    pub index: Option<String>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Query", variables = "SubclassSpellsQueryVariables")]
+pub struct SubclassSpellsQuery {
+    #[arguments(index: $index)]
+    pub subclass: Option<SubclassSpells>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Subclass")]
+pub struct SubclassSpells {
+    pub spells: Option<Vec<SpellWithPrerequisite>>,
+}
+
+impl SubclassSpells {
+    /// Returns the indexes of the spells whose prerequisites are met, grouped by spell level
+    /// # Arguments
+    /// * `class_level` - Level of the class of the subclass
+    /// * `land_terrain` - Index from https://www.dnd5eapi.co/api/features/ of the circle of the land terrain
+    pub fn available_spells(self, class_level: u8, land_terrain: Option<&str>) -> Vec<Vec<String>> {
+        let mut subclass_spells: Vec<Vec<String>> = Vec::new();
+
+        for spell in self.spells.unwrap_or_default() {
+            let available = spell.prerequisites.iter().flatten().all(|prerequisite| {
+                match prerequisite {
+                    SpellPrerequisite::Level(level) => level.level <= class_level as i32,
+                    // Circle of the land spells depend on the chosen terrain
+                    SpellPrerequisite::Feature(feature) => land_terrain == Some(&feature.index),
+                    SpellPrerequisite::Unknown => true,
+                }
+            });
+
+            if !available {
+                continue;
+            }
+
+            let level = spell.spell.level.max(0) as usize;
+            if subclass_spells.len() <= level {
+                subclass_spells.resize(level + 1, Vec::new());
+            }
+            subclass_spells[level].push(spell.spell.index);
+        }
+
+        subclass_spells
+    }
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+pub struct SpellWithPrerequisite {
+    pub prerequisites: Vec<Option<SpellPrerequisite>>,
+    pub spell: Spell,
+}
+
+#[derive(cynic::InlineFragments, Debug)]
+pub enum SpellPrerequisite {
+    Feature(SpellPrerequisiteFeature),
+    Level(SpellPrerequisiteLevel),
+    #[cynic(fallback)]
+    Unknown,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Feature")]
+pub struct SpellPrerequisiteFeature {
+    pub index: String,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Level")]
+pub struct SpellPrerequisiteLevel {
+    pub level: i32,
+}
+
 impl Class {
     /// Returns the spells that the class can cast
     /// If it's a knowladge based class it will return the spells that the character can know
@@ -35,6 +500,7 @@ impl Class {
     pub async fn get_spells(&self) -> Result<Vec<Spell>, ApiError> {
         let op = SpellsQuery::build(SpellsQueryVariables {
             class: Some(StringFilter(self.index().to_string())),
+            limit: SPELLS_QUERY_LIMIT,
         });
 
         let spells = Client::new()
@@ -50,18 +516,60 @@ impl Class {
     }
 
     pub async fn get_ready_spells(&self) -> Result<Vec<Vec<String>>, ApiError> {
-        match &self.1.spell_casting {
-            None => Ok(Vec::new()),
+        let mut ready_spells = match &self.1.spell_casting {
+            None => Vec::new(),
             Some(spell_casting) => match spell_casting {
-                ClassSpellCasting::KnowledgePrepared { .. } => Ok(Vec::new()),
+                ClassSpellCasting::KnowledgePrepared { .. } => Vec::new(),
                 ClassSpellCasting::AlreadyKnowPrepared {
                     spells_prepared_index,
                     ..
-                } => Ok(spells_prepared_index.clone()),
+                } => spells_prepared_index.clone(),
                 ClassSpellCasting::KnowledgeAlreadyPrepared { spells_index, .. } => {
-                    Ok(spells_index.clone())
+                    spells_index.clone()
                 }
             },
+        };
+
+        // Subclass spells are always prepared
+        for (level, spells) in self.1.subclass_spells_index.iter().enumerate() {
+            if ready_spells.len() <= level {
+                ready_spells.resize(level + 1, Vec::new());
+            }
+
+            for spell in spells {
+                if !ready_spells[level].contains(spell) {
+                    ready_spells[level].push(spell.clone());
+                }
+            }
         }
+
+        Ok(ready_spells)
+    }
+
+    /// Updates the always prepared spells granted by the subclass at the current level
+    /// e.g. https://www.dnd5eapi.co/api/features/domain-spells-1
+    pub async fn update_subclass_spells(&mut self) -> Result<(), ApiError> {
+        let Some(subclass) = &self.1.subclass else {
+            self.1.subclass_spells_index.clear();
+            return Ok(());
+        };
+
+        let op = SubclassSpellsQuery::build(SubclassSpellsQueryVariables {
+            index: Some(subclass.clone()),
+        });
+
+        let spells = Client::new()
+            .post(GRAPHQL_API_URL.as_str())
+            .run_graphql(op)
+            .await?
+            .data
+            .ok_or(ApiError::Schema)?
+            .subclass
+            .ok_or(ApiError::Schema)?;
+
+        self.1.subclass_spells_index =
+            spells.available_spells(self.1.level, self.1.druid_land_terrain.as_deref());
+
+        Ok(())
     }
 }
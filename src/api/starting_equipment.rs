@@ -0,0 +1,331 @@
+use super::shared::{ApiError, schema};
+use crate::starting_equipment::{
+    ItemQuantity, StartingEquipment, StartingEquipmentChoice, StartingEquipmentOption,
+};
+use crate::{Character, GRAPHQL_API_URL};
+use cynic::QueryBuilder;
+use cynic::http::ReqwestExt;
+use reqwest::Client;
+
+#[derive(cynic::QueryVariables, Debug)]
+pub struct StartingEquipmentQueryVariables {
+    pub index: Option<String>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Query", variables = "StartingEquipmentQueryVariables")]
+pub struct ClassStartingEquipmentQuery {
+    #[arguments(index: $index)]
+    pub class: Option<ClassStartingEquipment>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Query", variables = "StartingEquipmentQueryVariables")]
+pub struct BackgroundStartingEquipmentQuery {
+    #[arguments(index: $index)]
+    pub background: Option<BackgroundStartingEquipment>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Class")]
+pub struct ClassStartingEquipment {
+    #[cynic(rename = "starting_equipment")]
+    pub starting_equipment: Vec<Quantity>,
+    #[cynic(rename = "starting_equipment_options")]
+    pub starting_equipment_options: Vec<ApiStartingEquipmentChoice>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Background")]
+pub struct BackgroundStartingEquipment {
+    #[cynic(rename = "starting_equipment")]
+    pub starting_equipment: Vec<Quantity>,
+    #[cynic(rename = "starting_equipment_options")]
+    pub starting_equipment_options: Vec<EquipmentCategoryChoice>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+pub struct Quantity {
+    pub quantity: i32,
+    pub equipment: EquipmentIndex,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "IEquipment")]
+pub struct EquipmentIndex {
+    pub index: String,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "IEquipmentBase")]
+pub struct EquipmentBaseIndex {
+    pub index: String,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "StartingEquipmentChoice")]
+pub struct ApiStartingEquipmentChoice {
+    pub choose: i32,
+    pub desc: String,
+    pub from: StartingEquipmentOptionSet,
+}
+
+#[derive(cynic::InlineFragments, Debug)]
+pub enum StartingEquipmentOptionSet {
+    EquipmentCategoryOptionSet(EquipmentCategoryOptionSet),
+    EquipmentOptionSet(EquipmentOptionSet),
+    #[cynic(fallback)]
+    Unknown,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+pub struct EquipmentCategoryOptionSet {
+    #[cynic(rename = "equipment_category")]
+    pub equipment_category: EquipmentCategory,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+pub struct EquipmentCategory {
+    pub index: String,
+    pub name: String,
+    pub equipment: Vec<EquipmentBaseIndex>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+pub struct EquipmentOptionSet {
+    pub options: Vec<EquipmentOption>,
+}
+
+#[derive(cynic::InlineFragments, Debug)]
+pub enum EquipmentOption {
+    CountedReferenceOption(CountedReferenceOption),
+    EquipmentCategoryChoiceOption(EquipmentCategoryChoiceOption),
+    EquipmentMultipleOption(EquipmentMultipleOption),
+    #[cynic(fallback)]
+    Unknown,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+pub struct CountedReferenceOption {
+    pub count: i32,
+    pub of: EquipmentIndex,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+pub struct EquipmentCategoryChoiceOption {
+    pub choice: EquipmentCategoryChoice,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+pub struct EquipmentCategoryChoice {
+    pub choose: i32,
+    pub from: EquipmentCategoryOptionSet,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+pub struct EquipmentMultipleOption {
+    pub items: Vec<EquipmentMultipleItem>,
+}
+
+#[derive(cynic::InlineFragments, Debug)]
+pub enum EquipmentMultipleItem {
+    CountedReferenceOption(CountedReferenceOption),
+    EquipmentCategoryChoiceOption(EquipmentCategoryChoiceOption),
+    #[cynic(fallback)]
+    Unknown,
+}
+
+impl From<Quantity> for ItemQuantity {
+    fn from(quantity: Quantity) -> Self {
+        ItemQuantity {
+            index: quantity.equipment.index,
+            quantity: quantity.quantity.max(0) as u16,
+        }
+    }
+}
+
+impl From<CountedReferenceOption> for StartingEquipmentOption {
+    fn from(option: CountedReferenceOption) -> Self {
+        StartingEquipmentOption::Item(ItemQuantity {
+            index: option.of.index,
+            quantity: option.count.max(0) as u16,
+        })
+    }
+}
+
+impl From<EquipmentCategoryChoice> for StartingEquipmentOption {
+    fn from(choice: EquipmentCategoryChoice) -> Self {
+        let category = choice.from.equipment_category;
+
+        StartingEquipmentOption::Category {
+            choose: choice.choose.max(0) as u8,
+            category: category.index,
+            items: category
+                .equipment
+                .into_iter()
+                .map(|equipment| equipment.index)
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<EquipmentMultipleItem> for StartingEquipmentOption {
+    type Error = ApiError;
+
+    fn try_from(item: EquipmentMultipleItem) -> Result<Self, Self::Error> {
+        match item {
+            EquipmentMultipleItem::CountedReferenceOption(option) => Ok(option.into()),
+            EquipmentMultipleItem::EquipmentCategoryChoiceOption(option) => {
+                Ok(option.choice.into())
+            }
+            EquipmentMultipleItem::Unknown => Err(ApiError::Schema),
+        }
+    }
+}
+
+impl TryFrom<EquipmentOption> for StartingEquipmentOption {
+    type Error = ApiError;
+
+    fn try_from(option: EquipmentOption) -> Result<Self, Self::Error> {
+        match option {
+            EquipmentOption::CountedReferenceOption(option) => Ok(option.into()),
+            EquipmentOption::EquipmentCategoryChoiceOption(option) => Ok(option.choice.into()),
+            EquipmentOption::EquipmentMultipleOption(option) => {
+                Ok(StartingEquipmentOption::Multiple(
+                    option
+                        .items
+                        .into_iter()
+                        .map(StartingEquipmentOption::try_from)
+                        .collect::<Result<_, _>>()?,
+                ))
+            }
+            EquipmentOption::Unknown => Err(ApiError::Schema),
+        }
+    }
+}
+
+/// A whole category is a single option where the picks are made
+impl From<EquipmentCategoryChoice> for StartingEquipmentChoice {
+    fn from(choice: EquipmentCategoryChoice) -> Self {
+        StartingEquipmentChoice {
+            desc: choice.from.equipment_category.name.clone(),
+            choose: 1,
+            options: vec![choice.into()],
+        }
+    }
+}
+
+impl TryFrom<ApiStartingEquipmentChoice> for StartingEquipmentChoice {
+    type Error = ApiError;
+
+    fn try_from(choice: ApiStartingEquipmentChoice) -> Result<Self, Self::Error> {
+        let options = match choice.from {
+            StartingEquipmentOptionSet::EquipmentCategoryOptionSet(from) => {
+                let category_choice = EquipmentCategoryChoice {
+                    choose: choice.choose,
+                    from,
+                };
+
+                return Ok(StartingEquipmentChoice {
+                    desc: choice.desc,
+                    ..category_choice.into()
+                });
+            }
+            StartingEquipmentOptionSet::EquipmentOptionSet(from) => from
+                .options
+                .into_iter()
+                .map(StartingEquipmentOption::try_from)
+                .collect::<Result<_, _>>()?,
+            StartingEquipmentOptionSet::Unknown => return Err(ApiError::Schema),
+        };
+
+        Ok(StartingEquipmentChoice {
+            desc: choice.desc,
+            choose: choice.choose.max(0) as u8,
+            options,
+        })
+    }
+}
+
+/// Fetches the starting equipment of the class, with the choices left to the user
+/// # Arguments
+/// * `index` - Index from https://www.dnd5eapi.co/api/classes/
+pub async fn get_class_starting_equipment(index: &str) -> Result<StartingEquipment, ApiError> {
+    let op = ClassStartingEquipmentQuery::build(StartingEquipmentQueryVariables {
+        index: Some(index.to_string()),
+    });
+
+    let class = Client::new()
+        .post(GRAPHQL_API_URL.as_str())
+        .run_graphql(op)
+        .await?
+        .data
+        .ok_or(ApiError::Schema)?
+        .class
+        .ok_or(ApiError::Schema)?;
+
+    Ok(StartingEquipment {
+        items: class
+            .starting_equipment
+            .into_iter()
+            .map(ItemQuantity::from)
+            .collect(),
+        choices: class
+            .starting_equipment_options
+            .into_iter()
+            .map(StartingEquipmentChoice::try_from)
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// Fetches the starting equipment of the background, with the choices left to the user
+/// # Arguments
+/// * `index` - Index from https://www.dnd5eapi.co/api/backgrounds/
+pub async fn get_background_starting_equipment(index: &str) -> Result<StartingEquipment, ApiError> {
+    let op = BackgroundStartingEquipmentQuery::build(StartingEquipmentQueryVariables {
+        index: Some(index.to_string()),
+    });
+
+    let background = Client::new()
+        .post(GRAPHQL_API_URL.as_str())
+        .run_graphql(op)
+        .await?
+        .data
+        .ok_or(ApiError::Schema)?
+        .background
+        .ok_or(ApiError::Schema)?;
+
+    Ok(StartingEquipment {
+        items: background
+            .starting_equipment
+            .into_iter()
+            .map(ItemQuantity::from)
+            .collect(),
+        choices: background
+            .starting_equipment_options
+            .into_iter()
+            .map(StartingEquipmentChoice::from)
+            .collect(),
+    })
+}
+
+impl Character {
+    /// Adds the resolved starting equipment to the carried items of the inventory,
+    /// see StartingEquipment::resolve
+    /// Nothing is added if one of the items can't be fetched
+    pub async fn add_starting_equipment(&mut self, items: &[ItemQuantity]) -> Result<(), ApiError> {
+        let mut fetched = Vec::with_capacity(items.len());
+
+        for item in items {
+            let mut fetched_item = super::equipment::get_item(&item.index).await?;
+            fetched_item.quantity = item.quantity;
+            fetched.push(fetched_item);
+        }
+
+        for item in fetched {
+            self.insert_item(item);
+        }
+
+        Ok(())
+    }
+}
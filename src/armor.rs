@@ -0,0 +1,119 @@
+use crate::Character;
+use crate::abilities::Abilities;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Index from https://www.dnd5eapi.co/api/equipment-categories/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum ArmorCategory {
+    Light,
+    Medium,
+    Heavy,
+    Shield,
+}
+
+/// Stats of an armor or a shield
+/// https://www.dnd5eapi.co/api/equipment-categories/armor
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct Armor {
+    /// Index from https://www.dnd5eapi.co/api/equipment/
+    pub index: String,
+    pub category: ArmorCategory,
+    /// Base armor class, or the bonus for shields
+    pub base: u8,
+    /// If the dexterity modifier is added to the armor class
+    pub dex_bonus: bool,
+    /// The maximum of the dexterity modifier added, e.g. 2 for medium armor
+    pub max_dex_bonus: Option<u8>,
+    /// Strength score needed to not have the speed reduced by 10 feet
+    pub str_minimum: u8,
+    pub stealth_disadvantage: bool,
+    /// Bonus of magic armor, e.g. 1 for a +1 chain mail
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub magic_bonus: u8,
+}
+
+impl Armor {
+    /// Returns the armor class granted by the armor, or the bonus of a shield
+    pub fn armor_class(&self, abilities: &Abilities) -> i8 {
+        let dexterity = if self.dex_bonus {
+            let modifier = abilities.dexterity.modifier(0);
+
+            match self.max_dex_bonus {
+                Some(max) => modifier.min(max as i8),
+                None => modifier,
+            }
+        } else {
+            0
+        };
+
+        self.base as i8 + dexterity + self.magic_bonus as i8
+    }
+
+    /// Returns true if the strength is high enough to wear the armor without
+    /// having the speed reduced by 10 feet
+    pub fn strength_requirement_met(&self, abilities: &Abilities) -> bool {
+        abilities.strength.total() >= self.str_minimum
+    }
+}
+
+impl Character {
+    /// Calculates the armor class from the worn armor and shield,
+    /// without armor the best unarmored defense among the classes is used
+    /// The bonuses of the active magic items are added
+    pub fn class_armor(&self) -> i8 {
+        let abilities_score = self.abilities_score.lock().unwrap();
+
+        let mut armor_class = match self.worn_armor() {
+            Some(armor) => armor.armor_class(&abilities_score),
+            None => {
+                let dexterity = abilities_score.dexterity.modifier(0);
+
+                self.classes
+                    .0
+                    .values()
+                    .filter_map(|class| match class.index() {
+                        // https://www.dnd5eapi.co/api/features/monk-unarmored-defense
+                        "monk" if self.worn_shield().is_none() => {
+                            Some(10 + dexterity + abilities_score.wisdom.modifier(0))
+                        }
+                        // https://www.dnd5eapi.co/api/features/barbarian-unarmored-defense
+                        "barbarian" => {
+                            Some(10 + dexterity + abilities_score.constitution.modifier(0))
+                        }
+                        // https://www.dnd5eapi.co/api/features/draconic-resilience
+                        "sorcerer" if class.1.subclass.as_deref() == Some("draconic") => {
+                            Some(13 + dexterity)
+                        }
+                        _ => None,
+                    })
+                    .fold(10 + dexterity, i8::max)
+            }
+        };
+
+        if let Some(shield) = self.worn_shield() {
+            armor_class += shield.armor_class(&abilities_score);
+        }
+
+        // https://www.dnd5eapi.co/api/features/fighter-fighting-style-defense
+        if self.has_fighting_style("defense") && self.worn_armor().is_some() {
+            armor_class += 1;
+        }
+
+        armor_class += self.magic_item_armor_class_bonus();
+
+        armor_class
+    }
+
+    /// Returns true if the worn armor gives disadvantage on stealth checks
+    pub fn stealth_disadvantage(&self) -> bool {
+        self.worn_armor()
+            .is_some_and(|armor| armor.stealth_disadvantage)
+    }
+}
@@ -0,0 +1,258 @@
+use crate::Character;
+use crate::equipment::{Item, Weapon, WeaponRange};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// How the weapon is used for the attack
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum AttackMode {
+    /// The normal use of the weapon, in one hand or in both for two-handed weapons
+    Wielded,
+    /// A versatile weapon used with both hands
+    TwoHanded,
+    /// A melee weapon with the thrown property thrown at range
+    Thrown,
+    /// The bonus action attack with a light weapon held in the other hand
+    /// https://www.dnd5eapi.co/api/rule-sections/making-an-attack
+    OffHand,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct Attack {
+    /// Index from https://www.dnd5eapi.co/api/equipment/
+    pub index: String,
+    pub name: String,
+    pub mode: AttackMode,
+    /// Index from https://www.dnd5eapi.co/api/ability-scores/ used for the attack
+    pub ability: String,
+    /// Bonus to the attack roll
+    pub attack_bonus: i8,
+    /// Damage dice, e.g. 1d8, None for weapons without damage like the net
+    pub damage_dice: Option<String>,
+    /// Added to the damage roll
+    pub damage_bonus: i8,
+    /// Index from https://www.dnd5eapi.co/api/damage-types/
+    pub damage_type: Option<String>,
+    /// Damage dice showing 1 or 2 are rerolled, from the Great Weapon Fighting style
+    pub reroll_low_damage: bool,
+    /// Normal range in feet, 5 for melee attacks
+    pub normal_range: u16,
+    pub long_range: Option<u16>,
+}
+
+impl Attack {
+    /// Returns the damage roll, e.g. 1d8+3
+    pub fn damage(&self) -> String {
+        let dice = self.damage_dice.as_deref().unwrap_or("0");
+
+        match self.damage_bonus {
+            0 => dice.to_string(),
+            bonus => format!("{}{:+}", dice, bonus),
+        }
+    }
+}
+
+impl fmt::Display for Attack {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {:+} ({})",
+            self.name,
+            self.attack_bonus,
+            self.damage()
+        )
+    }
+}
+
+/// Returns true if the class grants the proficiency with the weapon
+/// https://www.dnd5eapi.co/api/classes/
+/// # Arguments
+/// * `class` - Index from https://www.dnd5eapi.co/api/classes/
+/// * `index` - Index from https://www.dnd5eapi.co/api/equipment/
+/// * `weapon` - The weapon stats, used for the category
+pub fn class_weapon_proficiency(class: &str, index: &str, weapon: &Weapon) -> bool {
+    let simple = weapon.category == "simple-weapons";
+
+    match class {
+        "barbarian" | "fighter" | "paladin" | "ranger" => true,
+        "cleric" | "warlock" => simple,
+        "bard" | "rogue" => {
+            simple
+                || matches!(
+                    index,
+                    "hand-crossbow" | "longsword" | "rapier" | "shortsword"
+                )
+        }
+        "monk" => simple || index == "shortsword",
+        "sorcerer" | "wizard" => matches!(
+            index,
+            "dagger" | "dart" | "sling" | "quarterstaff" | "crossbow-light"
+        ),
+        "druid" => matches!(
+            index,
+            "club"
+                | "dagger"
+                | "dart"
+                | "javelin"
+                | "mace"
+                | "quarterstaff"
+                | "scimitar"
+                | "sickle"
+                | "sling"
+                | "spear"
+        ),
+        _ => false,
+    }
+}
+
+impl Character {
+    /// Returns true if a class of the character has the fighting style
+    /// # Arguments
+    /// * `style` - End of the index of the fighting style, e.g. `archery` for
+    ///   https://www.dnd5eapi.co/api/features/fighter-fighting-style-archery
+    pub fn has_fighting_style(&self, style: &str) -> bool {
+        self.classes.0.values().any(|class| {
+            [&class.1.fighting_style, &class.1.additional_fighting_style]
+                .into_iter()
+                .flatten()
+                .any(|s| s.ends_with(style))
+        })
+    }
+
+    /// Returns true if any class of the character is proficient with the weapon
+    pub fn is_proficient_with(&self, item: &Item) -> bool {
+        item.weapon().is_some_and(|weapon| {
+            self.classes
+                .0
+                .keys()
+                .any(|class| class_weapon_proficiency(class, &item.index, weapon))
+        })
+    }
+
+    /// Returns the attacks that can be made with the equipped weapons
+    pub fn attacks(&self) -> Vec<Attack> {
+        let weapons: Vec<(&Item, &Weapon)> = self.equipped_weapons().collect();
+        let held: u16 = weapons.iter().map(|(item, _)| item.quantity).sum();
+
+        let light_melee = weapons
+            .iter()
+            .filter(|(_, weapon)| {
+                weapon.range == WeaponRange::Melee && weapon.has_property("light")
+            })
+            .map(|(item, _)| item.quantity)
+            .sum::<u16>();
+        let two_weapon_fighting = held == 2 && light_melee == 2;
+
+        let mut attacks = vec![];
+
+        for (item, weapon) in &weapons {
+            attacks.push(self.attack(item, weapon, AttackMode::Wielded, held));
+
+            if weapon.has_property("versatile") && held == 1 && self.worn_shield().is_none() {
+                attacks.push(self.attack(item, weapon, AttackMode::TwoHanded, held));
+            }
+
+            if weapon.range == WeaponRange::Melee && weapon.has_property("thrown") {
+                attacks.push(self.attack(item, weapon, AttackMode::Thrown, held));
+            }
+
+            if two_weapon_fighting {
+                attacks.push(self.attack(item, weapon, AttackMode::OffHand, held));
+            }
+        }
+
+        attacks
+    }
+
+    /// Calculates the attack with the weapon
+    /// # Arguments
+    /// * `held` - Number of weapons held, for the Dueling fighting style
+    fn attack(&self, item: &Item, weapon: &Weapon, mode: AttackMode, held: u16) -> Attack {
+        let (strength, dexterity) = {
+            let abilities = self.abilities_score.lock().unwrap();
+            (
+                abilities.strength.modifier(0),
+                abilities.dexterity.modifier(0),
+            )
+        };
+
+        let uses_dexterity = weapon.range == WeaponRange::Ranged
+            || (weapon.has_property("finesse") && dexterity > strength);
+
+        let (ability, modifier) = if uses_dexterity {
+            ("dex", dexterity)
+        } else {
+            ("str", strength)
+        };
+
+        let mut attack_bonus = modifier + self.magic_item_attack_bonus(Some(item));
+        if self.is_proficient_with(item) {
+            attack_bonus += self.proficiency_bonus() as i8;
+        }
+
+        // https://www.dnd5eapi.co/api/features/fighter-fighting-style-archery
+        if weapon.range == WeaponRange::Ranged && self.has_fighting_style("archery") {
+            attack_bonus += 2;
+        }
+
+        // The ability modifier is only added to the off-hand damage if it is negative
+        // https://www.dnd5eapi.co/api/features/fighter-fighting-style-two-weapon-fighting
+        let mut damage_bonus = if mode == AttackMode::OffHand
+            && modifier > 0
+            && !self.has_fighting_style("two-weapon-fighting")
+        {
+            0
+        } else {
+            modifier
+        };
+
+        let melee = weapon.range == WeaponRange::Melee && mode != AttackMode::Thrown;
+        let two_handed = mode == AttackMode::TwoHanded || weapon.has_property("two-handed");
+
+        // https://www.dnd5eapi.co/api/features/fighter-fighting-style-dueling
+        if melee && !two_handed && held == 1 && self.has_fighting_style("dueling") {
+            damage_bonus += 2;
+        }
+
+        // https://www.dnd5eapi.co/api/features/fighter-fighting-style-great-weapon-fighting
+        let reroll_low_damage =
+            melee && two_handed && self.has_fighting_style("great-weapon-fighting");
+
+        let damage_dice = match mode {
+            AttackMode::TwoHanded => weapon
+                .two_handed_damage_dice
+                .clone()
+                .or(weapon.damage_dice.clone()),
+            _ => weapon.damage_dice.clone(),
+        };
+
+        let (normal_range, long_range) = match mode {
+            AttackMode::Thrown => (
+                weapon.throw_normal_range.unwrap_or(weapon.normal_range),
+                weapon.throw_long_range,
+            ),
+            _ => (weapon.normal_range, weapon.long_range),
+        };
+
+        Attack {
+            index: item.index.clone(),
+            name: item.name.clone(),
+            mode,
+            ability: ability.to_string(),
+            attack_bonus,
+            damage_dice,
+            damage_bonus,
+            damage_type: weapon.damage_type.clone(),
+            reroll_low_damage,
+            normal_range,
+            long_range,
+        }
+    }
+}
@@ -1,4 +1,6 @@
 use crate::abilities::Abilities;
+use crate::spells::SpellDetails;
+use anyhow::bail;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::hash::Hash;
@@ -18,7 +20,6 @@ pub enum ClassSpellCasting {
     // Wizard
     // Ask the user to prepare spells at the start of the day
     //
-    // TODO: Add slots and consume them instead of removing from prepared
     // TODO: daily chosable spells = inteligence + level
     KnowledgePrepared {
         /// Indexes from https://www.dnd5eapi.co/api/spells/
@@ -27,12 +28,13 @@ pub enum ClassSpellCasting {
         spells_prepared_index: Vec<Vec<String>>,
         /// If the user has already prepared spells for the day
         pending_preparation: bool,
+        #[cfg_attr(feature = "serde", serde(default))]
+        usable_slots: UsableSlots,
     },
     // TEMP: Wizard
     // Cleric, Paladin, Druid
     // Ask the user to prepare spells at the start of the day
     //
-    // TODO: Add slots and consume them instead of removing from prepared
     // TODO: cleric/druid daily chosable spells = WISDOM + (level/2)
     // TODO: paladin daily chosable spells = CHARISMA + (level/2)
     AlreadyKnowPrepared {
@@ -40,6 +42,8 @@ pub enum ClassSpellCasting {
         spells_prepared_index: Vec<Vec<String>>,
         /// If the user has already prepared spells for the day
         pending_preparation: bool,
+        #[cfg_attr(feature = "serde", serde(default))]
+        usable_slots: UsableSlots,
     },
     // Bard, Ranger, Warlock, (Sorcerer?)
     // No need to ask anything, at the start of the day
@@ -55,6 +59,8 @@ pub enum ClassSpellCasting {
 #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 #[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct UsableSlots {
+    /// Number of cantrips known, cantrips don't use slots
+    /// Their damage scales with the character level, see Character::cantrip_damage
     pub cantrip_slots: u8,
     pub level_1: u8,
     pub level_2: u8,
@@ -67,6 +73,129 @@ pub struct UsableSlots {
     pub level_9: u8,
 }
 
+impl UsableSlots {
+    /// Returns the number of slots available for the given spell level (1-9)
+    pub fn slots(&self, level: u8) -> u8 {
+        match level {
+            1 => self.level_1,
+            2 => self.level_2,
+            3 => self.level_3,
+            4 => self.level_4,
+            5 => self.level_5,
+            6 => self.level_6,
+            7 => self.level_7,
+            8 => self.level_8,
+            9 => self.level_9,
+            _ => 0,
+        }
+    }
+
+    pub fn slots_mut(&mut self, level: u8) -> Option<&mut u8> {
+        match level {
+            1 => Some(&mut self.level_1),
+            2 => Some(&mut self.level_2),
+            3 => Some(&mut self.level_3),
+            4 => Some(&mut self.level_4),
+            5 => Some(&mut self.level_5),
+            6 => Some(&mut self.level_6),
+            7 => Some(&mut self.level_7),
+            8 => Some(&mut self.level_8),
+            9 => Some(&mut self.level_9),
+            _ => None,
+        }
+    }
+}
+
+/// https://www.dnd5eapi.co/api/features/flexible-casting-creating-spell-slots
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct SpellSlotCreation {
+    pub spell_slot_level: u8,
+    pub sorcery_point_cost: u8,
+}
+
+/// Returns the sorcery points needed to apply a metamagic to a spell of the given level
+/// # Arguments
+/// * `metamagic` - Index of the metamagic, e.g. `metamagic-quickened-spell`
+/// * `spell_level` - Level of the spell, 0 for cantrips
+pub fn metamagic_cost(metamagic: &str, spell_level: u8) -> Option<u16> {
+    match metamagic {
+        "metamagic-careful-spell"
+        | "metamagic-distant-spell"
+        | "metamagic-empowered-spell"
+        | "metamagic-extended-spell"
+        | "metamagic-subtle-spell" => Some(1),
+        "metamagic-quickened-spell" => Some(2),
+        "metamagic-heightened-spell" => Some(3),
+        // https://www.dnd5eapi.co/api/features/metamagic-twinned-spell
+        "metamagic-twinned-spell" => Some(spell_level.max(1) as u16),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum RestType {
+    Short,
+    Long,
+}
+
+/// Resources granted by a class that are spent and restored by resting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum ClassResourceType {
+    /// https://www.dnd5eapi.co/api/features/rage
+    Rage,
+    /// https://www.dnd5eapi.co/api/features/ki
+    Ki,
+    /// https://www.dnd5eapi.co/api/features/channel-divinity-1-rest
+    ChannelDivinity,
+    /// https://www.dnd5eapi.co/api/features/action-surge-1-use
+    ActionSurge,
+    /// https://www.dnd5eapi.co/api/features/indomitable-1-use
+    Indomitable,
+    /// https://www.dnd5eapi.co/api/features/font-of-magic
+    SorceryPoints,
+    /// https://www.dnd5eapi.co/api/features/bardic-inspiration-d6
+    BardicInspiration,
+    /// https://www.dnd5eapi.co/api/features/arcane-recovery
+    ArcaneRecovery,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ClassResource {
+    pub max: u16,
+    pub used: u16,
+    /// The shortest rest that restores this resource, a long rest restores everything
+    pub restored_on: RestType,
+    /// Size of the die rolled when using the resource, e.g. the bardic inspiration die
+    pub die: Option<u8>,
+}
+
+impl ClassResource {
+    pub fn new(max: u16, restored_on: RestType) -> Self {
+        Self {
+            max,
+            used: 0,
+            restored_on,
+            die: None,
+        }
+    }
+
+    pub fn remaining(&self) -> u16 {
+        self.max.saturating_sub(self.used)
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
@@ -88,7 +217,44 @@ pub struct ClassProperties {
     pub ranger_favored_enemy_type: Option<Vec<String>>,
     pub sorcerer_metamagic: Option<Vec<String>>,
     pub warlock_eldritch_invocation: Option<Vec<String>>,
+    /// Index of the chosen pact boon, e.g. pact-of-the-tome
+    /// https://www.dnd5eapi.co/api/features/pact-boon
+    pub warlock_pact: Option<String>,
+    /// Indexes from https://www.dnd5eapi.co/api/spells/ of the rituals written in the book
+    /// of the Book of Ancient Secrets invocation
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub warlock_ritual_book_index: Vec<String>,
+    /// Indexes from https://www.dnd5eapi.co/api/spells/ copied in the spellbook of the wizard,
+    /// the prepared spells are also part of it
+    /// https://www.dnd5eapi.co/api/features/spellcasting-wizard
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub wizard_spellbook_index: Vec<String>,
     pub sorcerer_dragon_ancestor: Option<String>,
+    /// Sorcery point cost of each spell slot level that can be created with Flexible Casting
+    pub sorcerer_slot_creation: Option<Vec<SpellSlotCreation>>,
+    /// Maximum combined level of the slots recovered with Arcane Recovery
+    pub wizard_arcane_recovery_levels: Option<u8>,
+    /// Bonus to the speed in feet while unarmored, see Character::speed_bonus
+    /// https://www.dnd5eapi.co/api/features/unarmored-movement-1
+    pub monk_unarmored_movement: Option<u8>,
+    /// Number of extra attacks with the Attack action, see Character::attacks_per_action
+    /// https://www.dnd5eapi.co/api/features/extra-attack-1
+    pub fighter_extra_attacks: Option<u8>,
+    /// Index from https://www.dnd5eapi.co/api/features/ of the circle of the land terrain
+    pub druid_land_terrain: Option<String>,
+    /// Indexes from https://www.dnd5eapi.co/api/spells/ granted by the subclass, grouped by spell level
+    /// They are always prepared and don't count against the number of prepared spells
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub subclass_spells_index: Vec<Vec<String>>,
+    /// Levels gained in this class whose hit points haven't been chosen yet
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub pending_hit_points: u8,
+    /// Resources populated from the class level data, e.g. rages or ki points
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub resources: HashMap<ClassResourceType, ClassResource>,
+    /// If the barbarian is raging, see ClassProperties::start_rage
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub raging: bool,
     #[cfg_attr(
         feature = "serde",
         serde(
@@ -101,6 +267,267 @@ pub struct ClassProperties {
     pub abilities: Arc<Mutex<Abilities>>,
 }
 
+impl ClassProperties {
+    /// Spends the given amount of a class resource
+    pub fn spend_resource(
+        &mut self,
+        resource: ClassResourceType,
+        amount: u16,
+    ) -> anyhow::Result<()> {
+        let Some(resource) = self.resources.get_mut(&resource) else {
+            bail!("Resource not available for this class")
+        };
+
+        if resource.remaining() < amount {
+            bail!("Not enough resource remaining")
+        }
+
+        resource.used += amount;
+
+        Ok(())
+    }
+
+    /// Gives back the given amount of a class resource, without exceeding its maximum
+    pub fn restore_resource(&mut self, resource: ClassResourceType, amount: u16) {
+        if let Some(resource) = self.resources.get_mut(&resource) {
+            resource.used = resource.used.saturating_sub(amount);
+        }
+    }
+
+    /// Spends a rage to start raging, it lasts 1 minute or until ClassProperties::end_rage
+    /// https://www.dnd5eapi.co/api/features/rage
+    pub fn start_rage(&mut self) -> anyhow::Result<()> {
+        if self.raging {
+            bail!("Already raging")
+        }
+
+        self.spend_resource(ClassResourceType::Rage, 1)?;
+        self.raging = true;
+
+        Ok(())
+    }
+
+    pub fn end_rage(&mut self) {
+        self.raging = false;
+    }
+
+    pub fn usable_slots(&self) -> Option<&UsableSlots> {
+        match &self.spell_casting {
+            Some(ClassSpellCasting::KnowledgePrepared { usable_slots, .. })
+            | Some(ClassSpellCasting::AlreadyKnowPrepared { usable_slots, .. })
+            | Some(ClassSpellCasting::KnowledgeAlreadyPrepared { usable_slots, .. }) => {
+                Some(usable_slots)
+            }
+            None => None,
+        }
+    }
+
+    pub fn usable_slots_mut(&mut self) -> Option<&mut UsableSlots> {
+        match &mut self.spell_casting {
+            Some(ClassSpellCasting::KnowledgePrepared { usable_slots, .. })
+            | Some(ClassSpellCasting::AlreadyKnowPrepared { usable_slots, .. })
+            | Some(ClassSpellCasting::KnowledgeAlreadyPrepared { usable_slots, .. }) => {
+                Some(usable_slots)
+            }
+            None => None,
+        }
+    }
+
+    /// Consumes the spell slot used to cast a spell and the sorcery points of the applied metamagic
+    /// Nothing is consumed if the slot or the sorcery points aren't available
+    /// # Arguments
+    /// * `slot_level` - Level of the slot used to cast the spell, 0 for cantrips
+    /// * `metamagic` - Indexes of the known metamagic applied to the spell
+    pub fn cast_spell(&mut self, slot_level: u8, metamagic: &[&str]) -> anyhow::Result<()> {
+        let mut metamagic_total = 0;
+        for metamagic in metamagic {
+            let known = self
+                .sorcerer_metamagic
+                .as_ref()
+                .is_some_and(|known| known.iter().any(|known| known == metamagic));
+
+            if !known {
+                bail!("Metamagic {} is not known", metamagic)
+            }
+
+            let Some(cost) = metamagic_cost(metamagic, slot_level) else {
+                bail!(
+                    "Metamagic {} has no sorcery point cost for a level {} slot",
+                    metamagic,
+                    slot_level
+                )
+            };
+            metamagic_total += cost;
+        }
+
+        if slot_level > 0 {
+            let Some(usable_slots) = self.usable_slots() else {
+                bail!("This class doesn't have spell slots")
+            };
+
+            if usable_slots.slots(slot_level) == 0 {
+                bail!("No spell slot of level {} available", slot_level)
+            }
+        }
+
+        if metamagic_total > 0 {
+            self.spend_resource(ClassResourceType::SorceryPoints, metamagic_total)?;
+        }
+
+        if let Some(slots) = self
+            .usable_slots_mut()
+            .and_then(|usable_slots| usable_slots.slots_mut(slot_level))
+        {
+            *slots -= 1;
+        }
+
+        Ok(())
+    }
+
+    /// Flexible Casting: spends sorcery points to create a spell slot of the given level
+    pub fn create_spell_slot(&mut self, slot_level: u8) -> anyhow::Result<()> {
+        let Some(cost) = self
+            .sorcerer_slot_creation
+            .as_ref()
+            .and_then(|creation| {
+                creation
+                    .iter()
+                    .find(|creation| creation.spell_slot_level == slot_level)
+            })
+            .map(|creation| creation.sorcery_point_cost)
+        else {
+            bail!("Cannot create a spell slot of level {}", slot_level)
+        };
+
+        if self.usable_slots().is_none() {
+            bail!("This class doesn't have spell slots")
+        }
+
+        self.spend_resource(ClassResourceType::SorceryPoints, cost as u16)?;
+
+        if let Some(slots) = self
+            .usable_slots_mut()
+            .and_then(|usable_slots| usable_slots.slots_mut(slot_level))
+        {
+            *slots += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Flexible Casting: expends a spell slot to gain sorcery points equal to its level
+    /// Returns an error if the sorcery points would go over the maximum of the current level
+    pub fn convert_spell_slot(&mut self, slot_level: u8) -> anyhow::Result<()> {
+        let Some(sorcery_points) = self.resources.get(&ClassResourceType::SorceryPoints) else {
+            bail!("This class doesn't have sorcery points")
+        };
+
+        if sorcery_points.used < slot_level as u16 {
+            bail!(
+                "Converting a spell slot of level {} would exceed the maximum sorcery points",
+                slot_level
+            )
+        }
+
+        let Some(slots) = self
+            .usable_slots_mut()
+            .and_then(|usable_slots| usable_slots.slots_mut(slot_level))
+        else {
+            bail!(
+                "This class doesn't have spell slots of level {}",
+                slot_level
+            )
+        };
+
+        if *slots == 0 {
+            bail!("No spell slot of level {} available", slot_level)
+        }
+
+        *slots -= 1;
+        self.restore_resource(ClassResourceType::SorceryPoints, slot_level as u16);
+
+        Ok(())
+    }
+
+    /// https://www.dnd5eapi.co/api/features/arcane-recovery
+    /// Checks that the given expended spell slots can be recovered, their combined level can't
+    /// exceed the arcane recovery levels and none of them can be 6th level or higher
+    /// Can be used once per day, when finishing a short rest
+    /// # Arguments
+    /// * `slots` - Level of each slot to recover
+    /// * `max_slots` - Slots of the class at its current level, used to check that the slots were expended
+    pub fn check_arcane_recovery(
+        &self,
+        slots: &[u8],
+        max_slots: &UsableSlots,
+    ) -> anyhow::Result<()> {
+        let Some(recovery_levels) = self.wizard_arcane_recovery_levels else {
+            bail!("This class doesn't have Arcane Recovery")
+        };
+
+        if slots.iter().any(|&level| level == 0 || level > 5) {
+            bail!("Only spell slots from 1st to 5th level can be recovered")
+        }
+
+        if slots.iter().map(|&level| level as u16).sum::<u16>() > recovery_levels as u16 {
+            bail!(
+                "The combined level of the slots can't exceed {}",
+                recovery_levels
+            )
+        }
+
+        let Some(usable_slots) = self.usable_slots() else {
+            bail!("This class doesn't have spell slots")
+        };
+
+        for level in 1..=5 {
+            let recovered = slots.iter().filter(|&&slot| slot == level).count();
+
+            if usable_slots.slots(level) as usize + recovered > max_slots.slots(level) as usize {
+                bail!("Not enough expended spell slots of level {}", level)
+            }
+        }
+
+        match self.resources.get(&ClassResourceType::ArcaneRecovery) {
+            Some(resource) if resource.remaining() > 0 => Ok(()),
+            Some(_) => bail!("Arcane Recovery was already used today"),
+            None => bail!("Resource not available for this class"),
+        }
+    }
+
+    /// Recovers the given expended spell slots, see ClassProperties::check_arcane_recovery
+    /// Only called at the end of the short rest by Character::short_rest_recovering_slots
+    pub(crate) fn arcane_recovery(
+        &mut self,
+        slots: &[u8],
+        max_slots: &UsableSlots,
+    ) -> anyhow::Result<()> {
+        self.check_arcane_recovery(slots, max_slots)?;
+        self.spend_resource(ClassResourceType::ArcaneRecovery, 1)?;
+
+        if let Some(usable_slots) = self.usable_slots_mut() {
+            for &level in slots {
+                if let Some(slots) = usable_slots.slots_mut(level) {
+                    *slots += 1;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Restores every resource that recovers with the given rest
+    pub fn rest(&mut self, rest: RestType) {
+        // A rage can't last longer than 1 minute
+        self.raging = false;
+
+        self.resources
+            .values_mut()
+            .filter(|resource| rest == RestType::Long || resource.restored_on == rest)
+            .for_each(|resource| resource.used = 0);
+    }
+}
+
 /// The key is the index of the class from https://www.dnd5eapi.co/api/classes
 #[derive(Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -145,6 +572,168 @@ impl Class {
             _ => 6,
         }
     }
+
+    /// Returns the index of the spellcasting ability from https://www.dnd5eapi.co/api/ability-scores/,
+    /// the same as Class::get_spellcasting_ability_index without a network call
+    /// None for classes that can't cast spells
+    pub fn spellcasting_ability(&self) -> Option<&'static str> {
+        match self.index() {
+            "bard" | "paladin" | "sorcerer" | "warlock" => Some("cha"),
+            "cleric" | "druid" | "ranger" => Some("wis"),
+            "wizard" => Some("int"),
+            _ => None,
+        }
+    }
+
+    /// Returns the modifier of the spellcasting ability
+    fn spellcasting_modifier(&self) -> Option<i8> {
+        let ability = self.spellcasting_ability()?;
+
+        self.1
+            .abilities
+            .lock()
+            .unwrap()
+            .get(ability)
+            .map(|ability| ability.modifier(0))
+    }
+
+    /// Returns the DC of the saving throws against the spells of the class,
+    /// 8 + the proficiency bonus + the spellcasting ability modifier
+    /// # Arguments
+    /// * `proficiency_bonus` - The proficiency bonus of the character, see Character::proficiency_bonus
+    pub fn spell_save_dc(&self, proficiency_bonus: u8) -> Option<u8> {
+        self.spellcasting_modifier()
+            .map(|modifier| (8 + proficiency_bonus as i8 + modifier).max(0) as u8)
+    }
+
+    /// Returns the bonus of the spell attacks of the class,
+    /// the proficiency bonus + the spellcasting ability modifier
+    /// # Arguments
+    /// * `proficiency_bonus` - The proficiency bonus of the character, see Character::proficiency_bonus
+    pub fn spell_attack_bonus(&self, proficiency_bonus: u8) -> Option<i8> {
+        self.spellcasting_modifier()
+            .map(|modifier| proficiency_bonus as i8 + modifier)
+    }
+
+    /// Returns true if the warlock has the book of rituals of the Pact of the Tome
+    /// https://www.dnd5eapi.co/api/features/eldritch-invocation-book-of-ancient-secrets
+    pub fn has_ritual_book(&self) -> bool {
+        self.1.warlock_pact.as_deref() == Some("pact-of-the-tome")
+            && self
+                .1
+                .warlock_eldritch_invocation
+                .as_ref()
+                .is_some_and(|invocations| {
+                    invocations.iter().any(|invocation| {
+                        invocation == "eldritch-invocation-book-of-ancient-secrets"
+                    })
+                })
+    }
+
+    /// Writes a ritual in the book of the Book of Ancient Secrets invocation,
+    /// its level can't be higher than half the warlock level, rounded up
+    /// # Arguments
+    /// * `spell` - The ritual, from any class, see api::spells::get_spell_details
+    pub fn add_ritual_to_book(&mut self, spell: &SpellDetails) -> anyhow::Result<()> {
+        if !self.has_ritual_book() {
+            bail!("This class doesn't have a book of rituals")
+        }
+
+        if !spell.ritual {
+            bail!("{} is not a ritual", spell.name)
+        }
+
+        if spell.level > self.1.level.div_ceil(2) {
+            bail!("{} is too high level to be written in the book", spell.name)
+        }
+
+        if !self.1.warlock_ritual_book_index.contains(&spell.index) {
+            self.1.warlock_ritual_book_index.push(spell.index.clone());
+        }
+
+        Ok(())
+    }
+
+    /// Copies a spell in the spellbook of the wizard, its level can't be higher than
+    /// half the wizard level, rounded up
+    /// # Arguments
+    /// * `spell` - The spell, see api::spells::get_spell_details
+    pub fn add_spell_to_spellbook(&mut self, spell: &SpellDetails) -> anyhow::Result<()> {
+        if self.index() != "wizard" {
+            bail!("This class doesn't have a spellbook")
+        }
+
+        if !spell.classes.is_empty() && !spell.classes.iter().any(|class| class == "wizard") {
+            bail!("{} is not a wizard spell", spell.name)
+        }
+
+        if spell.level == 0 || spell.level > self.1.level.div_ceil(2) {
+            bail!("{} can't be copied in the spellbook", spell.name)
+        }
+
+        if !self.1.wizard_spellbook_index.contains(&spell.index) {
+            self.1.wizard_spellbook_index.push(spell.index.clone());
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if the class can cast the spell as a ritual, without using a spell slot
+    /// Wizards can cast the rituals of their spellbook, clerics and druids their prepared
+    /// rituals, bards their known rituals and warlocks the rituals of the Book of Ancient Secrets
+    /// https://www.dnd5eapi.co/api/rule-sections/casting-a-spell
+    pub fn can_cast_as_ritual(&self, spell: &SpellDetails) -> bool {
+        fn contains(spells: &[Vec<String>], index: &str) -> bool {
+            spells.iter().flatten().any(|spell| spell == index)
+        }
+
+        if !spell.ritual {
+            return false;
+        }
+
+        let index = spell.index.as_str();
+
+        match (self.index(), &self.1.spell_casting) {
+            // https://www.dnd5eapi.co/api/features/ritual-casting-wizard
+            (
+                "wizard",
+                Some(ClassSpellCasting::KnowledgePrepared {
+                    spells_index: spells,
+                    ..
+                }),
+            )
+            | (
+                "wizard",
+                Some(ClassSpellCasting::AlreadyKnowPrepared {
+                    spells_prepared_index: spells,
+                    ..
+                }),
+            ) => {
+                contains(spells, index)
+                    || self.1.wizard_spellbook_index.iter().any(|s| s == index)
+                    || contains(&self.1.subclass_spells_index, index)
+            }
+            (
+                "cleric" | "druid",
+                Some(ClassSpellCasting::AlreadyKnowPrepared {
+                    spells_prepared_index: spells,
+                    ..
+                }),
+            )
+            | (
+                "bard",
+                Some(ClassSpellCasting::KnowledgeAlreadyPrepared {
+                    spells_index: spells,
+                    ..
+                }),
+            ) => contains(spells, index) || contains(&self.1.subclass_spells_index, index),
+            ("warlock", _) => {
+                self.has_ritual_book()
+                    && self.1.warlock_ritual_book_index.iter().any(|s| s == index)
+            }
+            _ => false,
+        }
+    }
 }
 
 #[derive(Default, Debug)]
@@ -167,6 +756,11 @@ impl<'de> Deserialize<'de> for Classes {
 }
 
 impl Classes {
+    /// Restores the resources of every class that recover with the given rest
+    pub fn rest(&mut self, rest: RestType) {
+        self.0.values_mut().for_each(|class| class.1.rest(rest));
+    }
+
     #[cfg(feature = "serde")]
     pub fn deserialize_with_abilities(
         value: serde_json::Value,
@@ -206,6 +800,7 @@ impl Classes {
                 Some(ClassSpellCasting::AlreadyKnowPrepared {
                     spells_prepared_index: Vec::new(),
                     pending_preparation: true,
+                    usable_slots: UsableSlots::default(),
                 })
             }
             "ranger" | "bard" | "warlock" | "sorcerer" => {
@@ -0,0 +1,129 @@
+use crate::Character;
+use crate::armor::ArmorCategory;
+use anyhow::bail;
+
+impl Character {
+    /// Half the proficiency bonus, rounded down, added to the ability checks of a bard
+    /// that don't already include it
+    /// https://www.dnd5eapi.co/api/features/jack-of-all-trades
+    pub fn jack_of_all_trades_bonus(&self) -> u8 {
+        match self.classes.0.get("bard") {
+            Some(bard) if bard.1.level >= 2 => self.proficiency_bonus() / 2,
+            _ => 0,
+        }
+    }
+
+    /// Half the proficiency bonus, rounded up, added to the strength, dexterity and
+    /// constitution checks of a champion that don't already include it
+    /// https://www.dnd5eapi.co/api/features/remarkable-athlete
+    pub fn remarkable_athlete_bonus(&self) -> u8 {
+        match self.classes.0.get("fighter") {
+            Some(fighter)
+                if fighter.1.level >= 7 && fighter.1.subclass.as_deref() == Some("champion") =>
+            {
+                self.proficiency_bonus().div_ceil(2)
+            }
+            _ => 0,
+        }
+    }
+
+    /// Returns the bonus of Jack of All Trades or Remarkable Athlete to a check of the ability
+    /// that doesn't include the proficiency bonus, they don't stack
+    fn unproficient_check_bonus(&self, ability: &str) -> u8 {
+        let remarkable_athlete = match ability {
+            "str" | "strength" | "dex" | "dexterity" | "con" | "constitution" => {
+                self.remarkable_athlete_bonus()
+            }
+            _ => 0,
+        };
+
+        self.jack_of_all_trades_bonus().max(remarkable_athlete)
+    }
+
+    /// Returns the bonus to the initiative, a dexterity check
+    /// https://www.dnd5eapi.co/api/rule-sections/combat-step-by-step
+    pub fn initiative(&self) -> i8 {
+        let dexterity = self.abilities_score.lock().unwrap().dexterity.modifier(0);
+
+        dexterity + self.unproficient_check_bonus("dex") as i8
+    }
+
+    /// Returns the passive score of a check, 10 + the ability modifier,
+    /// 5 less with disadvantage on ability checks
+    /// The skill proficiencies are not tracked, so only Jack of All Trades or
+    /// Remarkable Athlete is added
+    /// https://www.dnd5eapi.co/api/rule-sections/ability-checks
+    /// # Arguments
+    /// * `ability` - Index from https://www.dnd5eapi.co/api/ability-scores/
+    pub fn passive_score(&self, ability: &str) -> anyhow::Result<u8> {
+        let Some(modifier) = self
+            .abilities_score
+            .lock()
+            .unwrap()
+            .get(ability)
+            .map(|ability| ability.modifier(0))
+        else {
+            bail!("Unknown ability {}", ability)
+        };
+
+        let mut score = 10 + modifier as i16 + self.unproficient_check_bonus(ability) as i16;
+        if self.disadvantage_on_ability_checks() {
+            score -= 5;
+        }
+
+        Ok(score.max(0) as u8)
+    }
+
+    /// https://www.dnd5eapi.co/api/skills/perception
+    pub fn passive_perception(&self) -> u8 {
+        self.passive_score("wis").unwrap_or_default()
+    }
+
+    /// https://www.dnd5eapi.co/api/skills/investigation
+    pub fn passive_investigation(&self) -> u8 {
+        self.passive_score("int").unwrap_or_default()
+    }
+
+    /// https://www.dnd5eapi.co/api/skills/insight
+    pub fn passive_insight(&self) -> u8 {
+        self.passive_score("wis").unwrap_or_default()
+    }
+
+    /// Returns the bonus to the speed in feet from the class features
+    pub fn speed_bonus(&self) -> i32 {
+        let armor = self.worn_armor().map(|armor| armor.category);
+
+        self.classes
+            .0
+            .values()
+            .map(|class| match class.index() {
+                // https://www.dnd5eapi.co/api/features/unarmored-movement-1
+                "monk" if armor.is_none() && self.worn_shield().is_none() => {
+                    class.1.monk_unarmored_movement.unwrap_or(0) as i32
+                }
+                // https://www.dnd5eapi.co/api/features/fast-movement
+                "barbarian" if class.1.level >= 5 && armor != Some(ArmorCategory::Heavy) => 10,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Returns the number of attacks made with the Attack action,
+    /// the Extra Attack of several classes doesn't stack
+    /// https://www.dnd5eapi.co/api/features/extra-attack-1
+    pub fn attacks_per_action(&self) -> u8 {
+        let extra_attacks = self
+            .classes
+            .0
+            .values()
+            .map(|class| match class.index() {
+                "fighter" => class.1.fighter_extra_attacks.unwrap_or(0),
+                "barbarian" | "monk" | "paladin" | "ranger" if class.1.level >= 5 => 1,
+                _ => 0,
+            })
+            .max()
+            .unwrap_or(0);
+
+        1 + extra_attacks
+    }
+}
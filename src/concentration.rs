@@ -0,0 +1,131 @@
+use crate::Character;
+use crate::spells::SpellDetails;
+use anyhow::bail;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A spell whose effect is still active on the character
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ActiveEffect {
+    /// Index from https://www.dnd5eapi.co/api/spells/
+    pub spell: String,
+    pub name: String,
+    /// Level of the slot used to cast the spell, 0 for cantrips
+    pub slot_level: u8,
+    /// e.g. Up to 1 minute
+    pub duration: String,
+    pub concentration: bool,
+}
+
+/// The constitution saving throw needed to keep the concentration after taking damage
+/// https://www.dnd5eapi.co/api/rule-sections/casting-a-spell
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ConcentrationCheck {
+    /// Index from https://www.dnd5eapi.co/api/spells/ of the spell concentrated on
+    pub spell: String,
+    pub dc: u16,
+}
+
+/// Returns the DC of the concentration save, 10 or half the damage taken if higher
+pub fn concentration_save_dc(damage: u16) -> u16 {
+    (damage / 2).max(10)
+}
+
+impl Character {
+    /// Starts the effect of a spell that was just cast, spells with an instantaneous
+    /// duration have no lasting effect and are not tracked
+    /// Returns the spell the character was concentrating on if the new spell
+    /// requires concentration, as only one can be maintained at a time
+    /// # Arguments
+    /// * `spell` - The spell cast, see api::spells::get_spell_details
+    /// * `slot_level` - Level of the slot used, 0 for cantrips
+    pub fn start_spell_effect(
+        &mut self,
+        spell: &SpellDetails,
+        slot_level: u8,
+    ) -> Option<ActiveEffect> {
+        if spell.duration == "Instantaneous" {
+            return None;
+        }
+
+        let dropped = if spell.concentration {
+            self.end_concentration()
+        } else {
+            None
+        };
+
+        self.active_effects.push(ActiveEffect {
+            spell: spell.index.clone(),
+            name: spell.name.clone(),
+            slot_level,
+            duration: spell.duration.clone(),
+            concentration: spell.concentration,
+        });
+
+        dropped
+    }
+
+    /// Returns the effect of the spell the character is concentrating on
+    pub fn concentration(&self) -> Option<&ActiveEffect> {
+        self.active_effects
+            .iter()
+            .find(|effect| effect.concentration)
+    }
+
+    /// Stops concentrating, ending the effect of the spell
+    /// Returns the ended effect, None if the character wasn't concentrating
+    pub fn end_concentration(&mut self) -> Option<ActiveEffect> {
+        let position = self
+            .active_effects
+            .iter()
+            .position(|effect| effect.concentration)?;
+
+        Some(self.active_effects.remove(position))
+    }
+
+    /// Ends the effect of the spell, e.g. when its duration expires
+    /// # Arguments
+    /// * `spell` - Index from https://www.dnd5eapi.co/api/spells/
+    pub fn end_spell_effect(&mut self, spell: &str) -> anyhow::Result<ActiveEffect> {
+        let Some(position) = self
+            .active_effects
+            .iter()
+            .position(|effect| effect.spell == spell)
+        else {
+            bail!("{} is not active", spell)
+        };
+
+        Ok(self.active_effects.remove(position))
+    }
+
+    /// Resolves the constitution saving throw of a concentration check,
+    /// ending the concentration if it fails
+    /// Returns true if the concentration holds
+    /// # Arguments
+    /// * `check` - The check from the events of Character::take_damage
+    /// * `d20` - The result of the d20 rolled, the constitution saving throw modifier is added
+    pub fn resolve_concentration_check(
+        &mut self,
+        check: &ConcentrationCheck,
+        d20: u8,
+    ) -> anyhow::Result<bool> {
+        if self.concentration().map(|effect| &effect.spell) != Some(&check.spell) {
+            bail!("Not concentrating on {}", check.spell)
+        }
+
+        let total = d20 as i16 + self.saving_throw("con")? as i16;
+        let holds = total >= check.dc as i16;
+
+        if !holds {
+            self.end_concentration();
+        }
+
+        Ok(holds)
+    }
+}
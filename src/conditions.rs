@@ -0,0 +1,172 @@
+use crate::Character;
+use crate::classes::RestType;
+use anyhow::bail;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The character dies at this level of exhaustion
+/// https://www.dnd5eapi.co/api/conditions/exhaustion
+pub const MAX_EXHAUSTION: u8 = 6;
+
+/// How long a condition lasts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum ConditionDuration {
+    /// Until it is removed, e.g. by a spell
+    Indefinite,
+    /// Number of rounds left, see Character::end_round
+    Rounds(u16),
+    /// Ends with the rest, a long rest also ends the conditions lasting until a short rest
+    UntilRest(RestType),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct Condition {
+    /// Index from https://www.dnd5eapi.co/api/conditions/, e.g. poisoned
+    /// Exhaustion is tracked with Character::exhaustion
+    pub index: String,
+    /// What caused the condition, e.g. an index from https://www.dnd5eapi.co/api/spells/
+    pub source: Option<String>,
+    pub duration: ConditionDuration,
+}
+
+impl Character {
+    /// Adds the condition, replacing the same condition from the same source
+    /// Returns an error for exhaustion, see Character::add_exhaustion
+    pub fn add_condition(&mut self, condition: Condition) -> anyhow::Result<()> {
+        if condition.index == "exhaustion" {
+            bail!("Exhaustion has levels, use add_exhaustion")
+        }
+
+        self.conditions.retain(|existing| {
+            existing.index != condition.index || existing.source != condition.source
+        });
+        self.conditions.push(condition);
+
+        Ok(())
+    }
+
+    /// Removes the condition, only the one of the source if given
+    /// Returns an error if the character doesn't have the condition
+    /// # Arguments
+    /// * `index` - Index from https://www.dnd5eapi.co/api/conditions/
+    pub fn remove_condition(&mut self, index: &str, source: Option<&str>) -> anyhow::Result<()> {
+        let count = self.conditions.len();
+
+        self.conditions.retain(|condition| {
+            condition.index != index
+                || source.is_some_and(|source| condition.source.as_deref() != Some(source))
+        });
+
+        if self.conditions.len() == count {
+            bail!("The character is not {}", index)
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if the character has the condition, directly or through another one,
+    /// e.g. a stunned character is also incapacitated
+    /// # Arguments
+    /// * `index` - Index from https://www.dnd5eapi.co/api/conditions/
+    pub fn has_condition(&self, index: &str) -> bool {
+        let implied: &[&str] = match index {
+            "exhaustion" => return self.exhaustion > 0,
+            // https://www.dnd5eapi.co/api/conditions/incapacitated
+            "incapacitated" => &["paralyzed", "petrified", "stunned", "unconscious"],
+            _ => &[],
+        };
+
+        self.conditions.iter().any(|condition| {
+            condition.index == index || implied.contains(&condition.index.as_str())
+        })
+    }
+
+    /// Counts down the conditions lasting a number of rounds
+    /// Returns the conditions that ended
+    pub fn end_round(&mut self) -> Vec<Condition> {
+        for condition in &mut self.conditions {
+            if let ConditionDuration::Rounds(rounds) = &mut condition.duration {
+                *rounds = rounds.saturating_sub(1);
+            }
+        }
+
+        self.end_conditions(|duration| duration == ConditionDuration::Rounds(0))
+    }
+
+    /// Ends the conditions that last until the rest
+    /// Returns the conditions that ended
+    pub(crate) fn end_rest_conditions(&mut self, rest: RestType) -> Vec<Condition> {
+        self.end_conditions(|duration| match duration {
+            ConditionDuration::UntilRest(until) => rest == RestType::Long || until == rest,
+            _ => false,
+        })
+    }
+
+    fn end_conditions(&mut self, ends: impl Fn(ConditionDuration) -> bool) -> Vec<Condition> {
+        let (ended, remaining) = std::mem::take(&mut self.conditions)
+            .into_iter()
+            .partition(|condition| ends(condition.duration));
+        self.conditions = remaining;
+
+        ended
+    }
+
+    /// Adds levels of exhaustion, the character dies at the 6th level
+    /// The hit points are reduced if the hit point maximum is halved
+    pub fn add_exhaustion(&mut self, levels: u8) {
+        self.exhaustion = self.exhaustion.saturating_add(levels).min(MAX_EXHAUSTION);
+        self.hp = self.hp.min(self.max_hp());
+    }
+
+    pub fn remove_exhaustion(&mut self, levels: u8) {
+        self.exhaustion = self.exhaustion.saturating_sub(levels);
+    }
+
+    /// Returns true if the ability checks are made with disadvantage
+    pub fn disadvantage_on_ability_checks(&self) -> bool {
+        self.exhaustion >= 1 || self.has_condition("frightened") || self.has_condition("poisoned")
+    }
+
+    /// Returns true if the attack rolls are made with disadvantage
+    pub fn disadvantage_on_attacks(&self) -> bool {
+        self.exhaustion >= 3
+            || ["blinded", "frightened", "poisoned", "prone", "restrained"]
+                .iter()
+                .any(|condition| self.has_condition(condition))
+    }
+
+    /// Returns true if the saving throws of the ability are made with disadvantage
+    /// # Arguments
+    /// * `ability` - Index from https://www.dnd5eapi.co/api/ability-scores/
+    pub fn disadvantage_on_saving_throw(&self, ability: &str) -> bool {
+        self.exhaustion >= 3 || (ability == "dex" && self.has_condition("restrained"))
+    }
+
+    /// Returns the speed after the exhaustion and the conditions
+    pub(crate) fn conditions_speed(&self, speed: i32) -> i32 {
+        let immobile = [
+            "grappled",
+            "restrained",
+            "paralyzed",
+            "petrified",
+            "stunned",
+            "unconscious",
+        ]
+        .iter()
+        .any(|condition| self.has_condition(condition));
+
+        if immobile || self.exhaustion >= 5 {
+            0
+        } else if self.exhaustion >= 2 {
+            speed / 2
+        } else {
+            speed
+        }
+    }
+}
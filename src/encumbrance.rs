@@ -0,0 +1,84 @@
+use crate::Character;
+use crate::equipment::ItemState;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Encumbrance of the character with the variant rule
+/// https://www.dnd5eapi.co/api/rule-sections/lifting-and-carrying
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum Encumbrance {
+    Unencumbered,
+    /// Carrying more than 5 times the strength score, the speed drops by 10 feet
+    Encumbered,
+    /// Carrying more than 10 times the strength score, the speed drops by 20 feet
+    /// and the character has disadvantage on checks, attacks and saves using
+    /// strength, dexterity or constitution
+    HeavilyEncumbered,
+    /// Carrying more than the carrying capacity, the speed drops to 5 feet
+    OverCapacity,
+}
+
+impl Character {
+    /// Returns the weight in pounds of the equipped and carried items, stowed items are excluded
+    pub fn carried_weight(&self) -> f32 {
+        self.inventory
+            .iter()
+            .filter(|item| item.state != ItemState::Stowed)
+            .map(|item| item.weight * item.quantity as f32)
+            .sum()
+    }
+
+    /// Returns the weight in pounds the character can carry, 15 times the strength score
+    pub fn carrying_capacity(&self) -> u16 {
+        self.abilities_score.lock().unwrap().strength.total() as u16 * 15
+    }
+
+    /// Returns the weight in pounds the character can push, drag or lift,
+    /// twice the carrying capacity
+    pub fn push_drag_lift(&self) -> u16 {
+        self.carrying_capacity() * 2
+    }
+
+    pub fn encumbrance(&self) -> Encumbrance {
+        let strength = self.abilities_score.lock().unwrap().strength.total() as f32;
+        let weight = self.carried_weight();
+
+        if weight > strength * 15.0 {
+            Encumbrance::OverCapacity
+        } else if weight > strength * 10.0 {
+            Encumbrance::HeavilyEncumbered
+        } else if weight > strength * 5.0 {
+            Encumbrance::Encumbered
+        } else {
+            Encumbrance::Unencumbered
+        }
+    }
+
+    /// Returns the speed with the bonus of the class features, see Character::speed_bonus,
+    /// after the encumbrance, the strength requirement of the worn armor,
+    /// the exhaustion and the conditions
+    /// # Arguments
+    /// * `base_speed` - Speed of the race, see get_base_speed
+    pub fn effective_speed(&self, base_speed: i32) -> i32 {
+        let mut speed = base_speed + self.speed_bonus();
+
+        let strength_requirement_met = self.worn_armor().is_none_or(|armor| {
+            armor.strength_requirement_met(&self.abilities_score.lock().unwrap())
+        });
+        if !strength_requirement_met {
+            speed -= 10;
+        }
+
+        match self.encumbrance() {
+            Encumbrance::Unencumbered => {}
+            Encumbrance::Encumbered => speed -= 10,
+            Encumbrance::HeavilyEncumbered => speed -= 20,
+            Encumbrance::OverCapacity => speed = speed.min(5),
+        }
+
+        self.conditions_speed(speed.max(0))
+    }
+}
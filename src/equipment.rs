@@ -0,0 +1,303 @@
+use crate::Character;
+use crate::armor::{Armor, ArmorCategory};
+use crate::magic_items::MagicItem;
+use crate::money::Cost;
+use anyhow::bail;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Where the item is kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum ItemState {
+    /// Worn or wielded, e.g. the armor used for the armor class
+    Equipped,
+    /// Carried by the character, counts for the carried weight
+    #[default]
+    Carried,
+    /// Left elsewhere, e.g. on a mount or at the inn
+    Stowed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum WeaponRange {
+    Melee,
+    Ranged,
+}
+
+/// https://www.dnd5eapi.co/api/equipment-categories/weapon
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct Weapon {
+    /// Index from https://www.dnd5eapi.co/api/equipment-categories/, e.g. martial-weapons
+    pub category: String,
+    pub range: WeaponRange,
+    /// Damage dice, e.g. 1d8, None for weapons without damage like the net
+    pub damage_dice: Option<String>,
+    /// Index from https://www.dnd5eapi.co/api/damage-types/
+    pub damage_type: Option<String>,
+    /// Damage dice when used with two hands, only for versatile weapons
+    pub two_handed_damage_dice: Option<String>,
+    /// Normal range in feet, 5 for melee weapons
+    pub normal_range: u16,
+    pub long_range: Option<u16>,
+    pub throw_normal_range: Option<u16>,
+    pub throw_long_range: Option<u16>,
+    /// Indexes from https://www.dnd5eapi.co/api/weapon-properties/
+    pub properties: Vec<String>,
+}
+
+impl Weapon {
+    /// Returns true if the weapon has the property
+    /// # Arguments
+    /// * `property` - Index from https://www.dnd5eapi.co/api/weapon-properties/
+    pub fn has_property(&self, property: &str) -> bool {
+        self.properties.iter().any(|p| p == property)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum ItemDetails {
+    Weapon(Weapon),
+    Armor(Armor),
+    #[default]
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct Item {
+    /// Index from https://www.dnd5eapi.co/api/equipment/
+    pub index: String,
+    pub name: String,
+    pub quantity: u16,
+    /// Weight of one unit in pounds
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub weight: f32,
+    /// Price of one unit, None if unknown
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub cost: Option<Cost>,
+    /// Index from https://www.dnd5eapi.co/api/equipment-categories/
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub category: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub state: ItemState,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub details: ItemDetails,
+    /// None for mundane items
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub magic: Option<MagicItem>,
+}
+
+impl Item {
+    /// Item without any known metadata, used for the legacy inventory
+    /// and for items that aren't in the api
+    pub fn custom(name: String, quantity: u16) -> Self {
+        Self {
+            index: name.clone(),
+            name,
+            quantity,
+            weight: 0.0,
+            cost: None,
+            category: String::new(),
+            state: ItemState::Carried,
+            details: ItemDetails::Other,
+            magic: None,
+        }
+    }
+
+    pub fn armor(&self) -> Option<&Armor> {
+        match &self.details {
+            ItemDetails::Armor(armor) => Some(armor),
+            _ => None,
+        }
+    }
+
+    pub fn weapon(&self) -> Option<&Weapon> {
+        match &self.details {
+            ItemDetails::Weapon(weapon) => Some(weapon),
+            _ => None,
+        }
+    }
+
+    fn is_shield(&self) -> bool {
+        self.armor()
+            .is_some_and(|armor| armor.category == ArmorCategory::Shield)
+    }
+
+    fn is_body_armor(&self) -> bool {
+        self.armor().is_some() && !self.is_shield()
+    }
+}
+
+impl Character {
+    /// Adds the item to the inventory, stacking it with the same item in the same state
+    pub fn insert_item(&mut self, item: Item) {
+        match self.inventory.iter_mut().find(|i| {
+            i.index == item.index
+                && i.state == item.state
+                && i.details == item.details
+                && i.magic == item.magic
+        }) {
+            Some(stack) => stack.quantity = stack.quantity.saturating_add(item.quantity),
+            None => self.inventory.push(item),
+        }
+
+        self.update_magic_item_modifiers();
+    }
+
+    /// Adds more of an item already in the inventory, or an item without metadata
+    /// Use add_equipment to add an item from the api with its metadata
+    /// # Arguments
+    /// * `item` - Index from https://www.dnd5eapi.co/api/equipment/ or name of a custom item
+    pub fn add_item(&mut self, item: &str, amount: u16) {
+        let is_attuned = |i: &Item| i.magic.as_ref().is_some_and(|magic| magic.attuned);
+
+        // Growing an attuned stack would attune the new items too, they get their own stack
+        let stack = self
+            .inventory
+            .iter_mut()
+            .filter(|i| i.index == item && !is_attuned(i))
+            .min_by_key(|i| i.state != ItemState::Carried);
+
+        match stack {
+            Some(stack) => stack.quantity = stack.quantity.saturating_add(amount),
+            None => {
+                let new_stack = match self.inventory.iter().find(|i| i.index == item) {
+                    Some(attuned) => {
+                        let mut new_stack = attuned.clone();
+                        new_stack.quantity = amount;
+                        new_stack.state = ItemState::Carried;
+                        if let Some(magic) = &mut new_stack.magic {
+                            magic.attuned = false;
+                        }
+                        new_stack
+                    }
+                    None => Item::custom(item.to_string(), amount),
+                };
+                self.inventory.push(new_stack);
+            }
+        }
+
+        self.update_magic_item_modifiers();
+    }
+
+    /// Removes the amount of the item, or all of it if None,
+    /// taking the stowed items first and the equipped ones last
+    /// # Arguments
+    /// * `item` - Index from https://www.dnd5eapi.co/api/equipment/ or name of a custom item
+    pub fn remove_item(&mut self, item: &str, amount: Option<u16>) -> anyhow::Result<()> {
+        if !self.inventory.iter().any(|i| i.index == item) {
+            bail!("Item not found")
+        }
+
+        let mut remaining = amount.unwrap_or(u16::MAX);
+        for state in [ItemState::Stowed, ItemState::Carried, ItemState::Equipped] {
+            for stack in self
+                .inventory
+                .iter_mut()
+                .filter(|i| i.index == item && i.state == state)
+            {
+                let removed = stack.quantity.min(remaining);
+                stack.quantity -= removed;
+                remaining -= removed;
+            }
+        }
+
+        self.inventory.retain(|i| i.quantity > 0);
+        self.update_magic_item_modifiers();
+
+        Ok(())
+    }
+
+    /// Moves one unit of the item to the new state, taking it from the carried items
+    /// or from the equipped ones when moving it to the carried items
+    /// Equipping an armor or a shield moves the one already equipped to the carried items
+    /// # Arguments
+    /// * `index` - Index from https://www.dnd5eapi.co/api/equipment/
+    pub fn set_item_state(&mut self, index: &str, state: ItemState) -> anyhow::Result<()> {
+        let preferred = match state {
+            ItemState::Carried => ItemState::Equipped,
+            _ => ItemState::Carried,
+        };
+
+        let Some(position) = self
+            .inventory
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.index == index && item.state != state)
+            .min_by_key(|(_, item)| item.state != preferred)
+            .map(|(position, _)| position)
+        else {
+            bail!("No {} to move in the inventory", index)
+        };
+
+        let stack = &mut self.inventory[position];
+        let mut item = stack.clone();
+        item.quantity = 1;
+        item.state = state;
+
+        stack.quantity -= 1;
+        if stack.quantity == 0 {
+            self.inventory.remove(position);
+        }
+
+        if state == ItemState::Equipped && item.armor().is_some() {
+            let shield = item.is_shield();
+            let (replaced, kept): (Vec<Item>, Vec<Item>) = std::mem::take(&mut self.inventory)
+                .into_iter()
+                .partition(|i| {
+                    i.state == ItemState::Equipped && i.armor().is_some() && i.is_shield() == shield
+                });
+
+            self.inventory = kept;
+            for mut replaced in replaced {
+                replaced.state = ItemState::Carried;
+                self.insert_item(replaced);
+            }
+        }
+
+        self.insert_item(item);
+
+        Ok(())
+    }
+
+    /// Returns the equipped items
+    pub fn equipped_items(&self) -> impl Iterator<Item = &Item> {
+        self.inventory
+            .iter()
+            .filter(|item| item.state == ItemState::Equipped)
+    }
+
+    /// Returns the equipped armor, shields excluded
+    pub fn worn_armor(&self) -> Option<&Armor> {
+        self.equipped_items()
+            .find(|item| item.is_body_armor())
+            .and_then(Item::armor)
+    }
+
+    /// Returns the equipped shield
+    pub fn worn_shield(&self) -> Option<&Armor> {
+        self.equipped_items()
+            .find(|item| item.is_shield())
+            .and_then(Item::armor)
+    }
+
+    /// Returns the equipped weapons
+    pub fn equipped_weapons(&self) -> impl Iterator<Item = (&Item, &Weapon)> {
+        self.equipped_items()
+            .filter_map(|item| item.weapon().map(|weapon| (item, weapon)))
+    }
+}
@@ -0,0 +1,354 @@
+use crate::Character;
+use crate::concentration::{ConcentrationCheck, concentration_save_dc};
+use crate::conditions::{Condition, ConditionDuration, MAX_EXHAUSTION};
+use anyhow::bail;
+use rand::Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// How the hit points of a newly gained level are determined
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum HitPointsChoice {
+    /// Roll the class hit die
+    Roll,
+    /// Take the fixed average of the class hit die (half the die + 1)
+    Average,
+}
+
+/// Number of successes or failures that ends the death saving throws
+pub const DEATH_SAVES_LIMIT: u8 = 3;
+
+/// Death saving throws made while the character is at 0 hit points
+/// https://www.dnd5eapi.co/api/rule-sections/damage-and-healing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct DeathSaves {
+    pub successes: u8,
+    /// The character is dead at 3 failures
+    pub failures: u8,
+    /// The character is at 0 hit points but doesn't make death saving throws
+    pub stable: bool,
+}
+
+/// What happened to the character after a change of its hit points
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum HitPointEvent {
+    DamageTaken {
+        /// After the resistances, immunities and vulnerabilities
+        amount: u16,
+        /// Index from https://www.dnd5eapi.co/api/damage-types/
+        damage_type: String,
+    },
+    /// Damage absorbed by the temporary hit points
+    TemporaryHitPointsLost(u16),
+    HitPointsLost(u16),
+    HitPointsRegained(u16),
+    /// Dropped to 0 hit points, the character has the unconscious condition
+    /// and starts making death saving throws
+    Unconscious,
+    /// Regained hit points while at 0 hit points
+    Conscious,
+    DeathSaveSucceeded {
+        successes: u8,
+    },
+    DeathSaveFailed {
+        failures: u8,
+    },
+    /// No more death saving throws are needed
+    Stabilized,
+    /// From 3 failed death saving throws or massive damage
+    Died,
+    /// The constitution saving throw needed to keep concentrating,
+    /// see Character::resolve_concentration_check
+    ConcentrationCheck(ConcentrationCheck),
+    /// Index from https://www.dnd5eapi.co/api/spells/ of the spell no longer concentrated on
+    ConcentrationLost(String),
+}
+
+impl Character {
+    /// Returns true if the character failed 3 death saving throws, took massive damage
+    /// or reached the 6th level of exhaustion
+    pub fn is_dead(&self) -> bool {
+        self.death_saves.failures >= DEATH_SAVES_LIMIT || self.exhaustion >= MAX_EXHAUSTION
+    }
+
+    /// Returns true if the character is at 0 hit points and still alive
+    pub fn is_unconscious(&self) -> bool {
+        self.hp == 0 && !self.is_dead()
+    }
+
+    /// Returns true if the character is unconscious and must make death saving throws
+    pub fn is_dying(&self) -> bool {
+        self.is_unconscious() && !self.death_saves.stable
+    }
+
+    /// Gives temporary hit points, they don't stack so the highest amount is kept
+    pub fn set_temporary_hp(&mut self, amount: u16) {
+        self.temporary_hp = self.temporary_hp.max(amount);
+    }
+
+    /// Applies the resistances of the character to the damage, see
+    /// Character::effective_damage_resistances, and removes it from the temporary
+    /// hit points first, then from the hit points
+    /// Damage at 0 hit points causes a failed death saving throw, and kills the character
+    /// if the damage remaining after dropping to 0 is at least the hit point maximum
+    /// Nothing happens to a dead character
+    /// # Arguments
+    /// * `amount` - The damage taken
+    /// * `damage_type` - Index from https://www.dnd5eapi.co/api/damage-types/
+    pub fn take_damage(&mut self, amount: u16, damage_type: &str) -> Vec<HitPointEvent> {
+        let amount = self
+            .effective_damage_resistances()
+            .apply(amount, damage_type);
+
+        if self.is_dead() || amount == 0 {
+            return vec![];
+        }
+
+        let mut events = vec![HitPointEvent::DamageTaken {
+            amount,
+            damage_type: damage_type.to_string(),
+        }];
+
+        let absorbed = self.temporary_hp.min(amount);
+        if absorbed > 0 {
+            self.temporary_hp -= absorbed;
+            events.push(HitPointEvent::TemporaryHitPointsLost(absorbed));
+        }
+
+        let remaining = amount - absorbed;
+        if remaining == 0 {
+            self.push_concentration_check(amount, &mut events);
+            return events;
+        }
+
+        if self.hp == 0 {
+            if remaining >= self.max_hp() {
+                self.die(&mut events);
+            } else {
+                self.fail_death_saves(1, &mut events);
+            }
+            return events;
+        }
+
+        let lost = self.hp.min(remaining);
+        self.hp -= lost;
+        events.push(HitPointEvent::HitPointsLost(lost));
+
+        if self.hp > 0 {
+            self.push_concentration_check(amount, &mut events);
+        } else if remaining - lost >= self.max_hp() {
+            // https://www.dnd5eapi.co/api/rule-sections/damage-and-healing
+            self.die(&mut events);
+        } else {
+            self.death_saves = DeathSaves::default();
+            self.conditions.push(Condition {
+                index: "unconscious".to_string(),
+                source: None,
+                duration: ConditionDuration::Indefinite,
+            });
+            events.push(HitPointEvent::Unconscious);
+            self.push_concentration_lost(&mut events);
+        }
+
+        events
+    }
+
+    /// Regains hit points up to the hit point maximum,
+    /// an unconscious character regains consciousness
+    /// Returns an error if the character is dead
+    pub fn heal(&mut self, amount: u16) -> anyhow::Result<Vec<HitPointEvent>> {
+        if self.is_dead() {
+            bail!("A dead character can't be healed")
+        }
+
+        let mut events = vec![];
+
+        let regained = self.max_hp().saturating_sub(self.hp).min(amount);
+        if regained == 0 {
+            return Ok(events);
+        }
+
+        if self.hp == 0 {
+            self.death_saves = DeathSaves::default();
+            self.conditions
+                .retain(|condition| condition.index != "unconscious" || condition.source.is_some());
+            events.push(HitPointEvent::Conscious);
+        }
+
+        self.hp += regained;
+        events.push(HitPointEvent::HitPointsRegained(regained));
+
+        Ok(events)
+    }
+
+    /// Resolves a death saving throw, 10 or higher is a success
+    /// A 1 counts as two failures and a 20 regains 1 hit point
+    /// Returns an error if the character is not dying or if the d20 result is not between 1 and 20
+    /// # Arguments
+    /// * `d20` - The result of the d20 rolled
+    pub fn death_saving_throw(&mut self, d20: u8) -> anyhow::Result<Vec<HitPointEvent>> {
+        if !(1..=20).contains(&d20) {
+            bail!("Invalid d20 result {}", d20)
+        }
+
+        if !self.is_dying() {
+            bail!("The character is not dying")
+        }
+
+        let mut events = vec![];
+
+        match d20 {
+            20 => return self.heal(1),
+            10.. => {
+                self.death_saves.successes += 1;
+                events.push(HitPointEvent::DeathSaveSucceeded {
+                    successes: self.death_saves.successes,
+                });
+
+                if self.death_saves.successes >= DEATH_SAVES_LIMIT {
+                    self.death_saves.stable = true;
+                    events.push(HitPointEvent::Stabilized);
+                }
+            }
+            1 => self.fail_death_saves(2, &mut events),
+            _ => self.fail_death_saves(1, &mut events),
+        }
+
+        Ok(events)
+    }
+
+    /// Stabilizes a dying character, e.g. with a medicine check or spare the dying
+    /// Returns an error if the character is not dying
+    pub fn stabilize(&mut self) -> anyhow::Result<Vec<HitPointEvent>> {
+        if !self.is_dying() {
+            bail!("The character is not dying")
+        }
+
+        self.death_saves.stable = true;
+
+        Ok(vec![HitPointEvent::Stabilized])
+    }
+
+    fn fail_death_saves(&mut self, failures: u8, events: &mut Vec<HitPointEvent>) {
+        self.death_saves.stable = false;
+        self.death_saves.failures = (self.death_saves.failures + failures).min(DEATH_SAVES_LIMIT);
+        events.push(HitPointEvent::DeathSaveFailed {
+            failures: self.death_saves.failures,
+        });
+
+        if self.is_dead() {
+            events.push(HitPointEvent::Died);
+        }
+    }
+
+    fn die(&mut self, events: &mut Vec<HitPointEvent>) {
+        self.hp = 0;
+        self.death_saves.failures = DEATH_SAVES_LIMIT;
+        events.push(HitPointEvent::Died);
+        self.push_concentration_lost(events);
+    }
+
+    fn push_concentration_check(&self, damage: u16, events: &mut Vec<HitPointEvent>) {
+        if let Some(effect) = self.concentration() {
+            events.push(HitPointEvent::ConcentrationCheck(ConcentrationCheck {
+                spell: effect.spell.clone(),
+                dc: concentration_save_dc(damage),
+            }));
+        }
+    }
+
+    fn push_concentration_lost(&mut self, events: &mut Vec<HitPointEvent>) {
+        if let Some(effect) = self.end_concentration() {
+            events.push(HitPointEvent::ConcentrationLost(effect.spell));
+        }
+    }
+
+    /// Extra hit points granted for every character level
+    /// https://www.dnd5eapi.co/api/traits/dwarven-toughness
+    /// https://www.dnd5eapi.co/api/feats/tough
+    pub fn hp_bonus_per_level(&self) -> u16 {
+        let mut bonus = 0;
+
+        if self.subrace_index == "hill-dwarf" {
+            bonus += 1;
+        }
+
+        if self.has_feat("tough") {
+            bonus += 2;
+        }
+
+        bonus
+    }
+
+    /// Returns the classes that have levels whose hit points still need to be chosen
+    /// and how many levels are pending for each of them
+    pub fn pending_hit_points(&self) -> Vec<(String, u8)> {
+        self.classes
+            .0
+            .iter()
+            .filter(|(_, class)| class.1.pending_hit_points > 0)
+            .map(|(index, class)| (index.clone(), class.1.pending_hit_points))
+            .collect()
+    }
+
+    /// Resolves one pending level of the given class, adding the hit die result to the character
+    /// The very first level of the character always takes the maximum of the hit die
+    /// Returns the hit die result that has been added
+    /// # Arguments
+    /// * `class_index` - Index of the class that gained the level
+    /// * `choice` - Whether to roll the hit die or take the fixed average
+    /// * `rng` - The random generator used to roll, use a seeded one for reproducible results
+    pub fn choose_hit_points<R: Rng + ?Sized>(
+        &mut self,
+        class_index: &str,
+        choice: HitPointsChoice,
+        rng: &mut R,
+    ) -> anyhow::Result<u16> {
+        let first_level = self.hit_dice_result == 0;
+
+        let Some(class) = self.classes.0.get_mut(class_index) else {
+            bail!("Class not found")
+        };
+
+        if class.1.pending_hit_points == 0 {
+            bail!("No pending hit points for this class")
+        }
+
+        let hit_dice = class.hit_dice() as u16;
+
+        let result = match choice {
+            _ if first_level => hit_dice,
+            HitPointsChoice::Roll => rng.gen_range(1..=hit_dice),
+            HitPointsChoice::Average => hit_dice / 2 + 1,
+        };
+
+        class.1.pending_hit_points -= 1;
+
+        self.hit_dice_result = self.hit_dice_result.saturating_add(result);
+
+        // The constitution modifier for the new level is already part of max_hp since it
+        // follows the character level, so the current hp gains the whole level worth of hp
+        let constitution_modifier = self
+            .abilities_score
+            .lock()
+            .unwrap()
+            .constitution
+            .modifier(0);
+        let gained = (result as i32
+            + constitution_modifier as i32
+            + self.hp_bonus_per_level() as i32)
+            .max(0) as u16;
+        self.hp = self.hp.saturating_add(gained).min(self.max_hp());
+
+        Ok(result)
+    }
+}
@@ -2,7 +2,20 @@
 pub mod api;
 
 pub mod abilities;
+pub mod armor;
+pub mod attacks;
 pub mod classes;
+pub mod combat;
+pub mod concentration;
+pub mod conditions;
+pub mod encumbrance;
+pub mod equipment;
+pub mod hit_points;
+pub mod magic_items;
+pub mod money;
+pub mod resistances;
+pub mod spells;
+pub mod starting_equipment;
 
 use abilities::AbilityScore;
 use anyhow::bail;
@@ -10,12 +23,20 @@ use lazy_static::lazy_static;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
+#[cfg(feature = "serde")]
 use std::collections::HashMap;
 use std::fmt;
 use std::sync::{Arc, Mutex};
 
 use crate::abilities::Abilities;
-use crate::classes::Classes;
+use crate::classes::{Classes, RestType, UsableSlots};
+use crate::concentration::ActiveEffect;
+use crate::conditions::Condition;
+use crate::equipment::Item;
+use crate::hit_points::DeathSaves;
+use crate::magic_items::Recharge;
+use crate::money::Purse;
+use crate::resistances::DamageResistances;
 
 #[cfg(feature = "serde")]
 mod abilities_score_serde {
@@ -112,7 +133,7 @@ pub struct Character {
 
     experience_points: u32,
 
-    pub money: u32,
+    pub money: Purse,
 
     #[cfg_attr(feature = "serde", serde(with = "abilities_score_serde"))]
     #[cfg_attr(feature = "utoipa", schema(value_type = Abilities))]
@@ -122,8 +143,32 @@ pub struct Character {
     pub hp: u16,
     #[serde(default = "default_hit_dice")]
     pub hit_dice_result: u16,
-
-    pub inventory: HashMap<String, u16>,
+    /// Absorb the damage before the hit points, see Character::set_temporary_hp
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub temporary_hp: u16,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub death_saves: DeathSaves,
+    /// Added by the user, e.g. from a spell or the draconic ancestry of a dragonborn,
+    /// see Character::effective_damage_resistances for all of them
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub damage_resistances: DamageResistances,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub conditions: Vec<Condition>,
+    /// Level of exhaustion from 0 to 6, see Character::add_exhaustion
+    /// https://www.dnd5eapi.co/api/conditions/exhaustion
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub exhaustion: u8,
+
+    /// Effects of the spells cast that are still active
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub active_effects: Vec<ActiveEffect>,
+
+    pub inventory: Vec<Item>,
+
+    /// Indexes from https://www.dnd5eapi.co/api/feats/, including the ones chosen instead of
+    /// an ability score improvement of any class
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub feats: Vec<String>,
 
     pub other: Vec<String>,
 }
@@ -146,17 +191,49 @@ struct CharacterDeserializeHelper {
     background_index: String,
     background_description: String,
     experience_points: u32,
-    money: u32,
+    money: LegacyMoney,
     abilities_score: Abilities,
     hp: u16,
     #[serde(default = "default_hit_dice")]
     hit_dice_result: u16,
-    inventory: HashMap<String, u16>,
+    #[serde(default)]
+    temporary_hp: u16,
+    #[serde(default)]
+    death_saves: DeathSaves,
+    #[serde(default)]
+    damage_resistances: DamageResistances,
+    #[serde(default)]
+    conditions: Vec<Condition>,
+    #[serde(default)]
+    exhaustion: u8,
+    #[serde(default)]
+    active_effects: Vec<ActiveEffect>,
+    inventory: LegacyInventory,
+    #[serde(default)]
+    feats: Vec<String>,
     other: Vec<String>,
     #[serde(default)]
     classes: serde_json::Value,
 }
 
+/// The money used to be a single amount, considered as gold pieces
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LegacyMoney {
+    Purse(Purse),
+    Gold(u32),
+}
+
+/// The inventory used to be a map of item names to quantities
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LegacyInventory {
+    Items(Vec<Item>),
+    Quantities(HashMap<String, u16>),
+}
+
 #[cfg(feature = "serde")]
 impl From<CharacterDeserializeHelper> for Character {
     fn from(helper: CharacterDeserializeHelper) -> Self {
@@ -170,6 +247,14 @@ impl From<CharacterDeserializeHelper> for Character {
                 Err(_) => Classes::default(),
             };
 
+        let inventory = match helper.inventory {
+            LegacyInventory::Items(items) => items,
+            LegacyInventory::Quantities(quantities) => quantities
+                .into_iter()
+                .map(|(name, quantity)| Item::custom(name, quantity))
+                .collect(),
+        };
+
         Self {
             classes,
             name: helper.name,
@@ -181,11 +266,24 @@ impl From<CharacterDeserializeHelper> for Character {
             background_index: helper.background_index,
             background_description: helper.background_description,
             experience_points: helper.experience_points,
-            money: helper.money,
+            money: match helper.money {
+                LegacyMoney::Purse(purse) => purse,
+                LegacyMoney::Gold(gp) => Purse {
+                    gp,
+                    ..Purse::default()
+                },
+            },
             abilities_score,
             hp: helper.hp,
             hit_dice_result: helper.hit_dice_result,
-            inventory: helper.inventory,
+            temporary_hp: helper.temporary_hp,
+            death_saves: helper.death_saves,
+            damage_resistances: helper.damage_resistances,
+            conditions: helper.conditions,
+            exhaustion: helper.exhaustion,
+            active_effects: helper.active_effects,
+            inventory,
+            feats: helper.feats,
             other: helper.other,
         }
     }
@@ -220,6 +318,178 @@ pub mod utoipa_addon {
                     super::classes::UsableSlots::name().to_string(),
                     super::classes::UsableSlots::schema(),
                 );
+                components.schemas.insert(
+                    super::classes::ClassResource::name().to_string(),
+                    super::classes::ClassResource::schema(),
+                );
+                components.schemas.insert(
+                    super::classes::ClassResourceType::name().to_string(),
+                    super::classes::ClassResourceType::schema(),
+                );
+                components.schemas.insert(
+                    super::classes::RestType::name().to_string(),
+                    super::classes::RestType::schema(),
+                );
+                components.schemas.insert(
+                    super::classes::SpellSlotCreation::name().to_string(),
+                    super::classes::SpellSlotCreation::schema(),
+                );
+                components.schemas.insert(
+                    super::armor::Armor::name().to_string(),
+                    super::armor::Armor::schema(),
+                );
+                components.schemas.insert(
+                    super::armor::ArmorCategory::name().to_string(),
+                    super::armor::ArmorCategory::schema(),
+                );
+                components.schemas.insert(
+                    super::encumbrance::Encumbrance::name().to_string(),
+                    super::encumbrance::Encumbrance::schema(),
+                );
+                components.schemas.insert(
+                    super::money::Currency::name().to_string(),
+                    super::money::Currency::schema(),
+                );
+                components.schemas.insert(
+                    super::money::Cost::name().to_string(),
+                    super::money::Cost::schema(),
+                );
+                components.schemas.insert(
+                    super::money::Purse::name().to_string(),
+                    super::money::Purse::schema(),
+                );
+                components.schemas.insert(
+                    super::equipment::ItemState::name().to_string(),
+                    super::equipment::ItemState::schema(),
+                );
+                components.schemas.insert(
+                    super::equipment::WeaponRange::name().to_string(),
+                    super::equipment::WeaponRange::schema(),
+                );
+                components.schemas.insert(
+                    super::equipment::Weapon::name().to_string(),
+                    super::equipment::Weapon::schema(),
+                );
+                components.schemas.insert(
+                    super::equipment::ItemDetails::name().to_string(),
+                    super::equipment::ItemDetails::schema(),
+                );
+                components.schemas.insert(
+                    super::equipment::Item::name().to_string(),
+                    super::equipment::Item::schema(),
+                );
+                components.schemas.insert(
+                    super::starting_equipment::ItemQuantity::name().to_string(),
+                    super::starting_equipment::ItemQuantity::schema(),
+                );
+                components.schemas.insert(
+                    super::starting_equipment::StartingEquipmentOption::name().to_string(),
+                    super::starting_equipment::StartingEquipmentOption::schema(),
+                );
+                components.schemas.insert(
+                    super::starting_equipment::StartingEquipmentChoice::name().to_string(),
+                    super::starting_equipment::StartingEquipmentChoice::schema(),
+                );
+                components.schemas.insert(
+                    super::starting_equipment::StartingEquipmentSelection::name().to_string(),
+                    super::starting_equipment::StartingEquipmentSelection::schema(),
+                );
+                components.schemas.insert(
+                    super::starting_equipment::StartingEquipment::name().to_string(),
+                    super::starting_equipment::StartingEquipment::schema(),
+                );
+                components.schemas.insert(
+                    super::magic_items::Rarity::name().to_string(),
+                    super::magic_items::Rarity::schema(),
+                );
+                components.schemas.insert(
+                    super::magic_items::MagicItemEffect::name().to_string(),
+                    super::magic_items::MagicItemEffect::schema(),
+                );
+                components.schemas.insert(
+                    super::magic_items::Recharge::name().to_string(),
+                    super::magic_items::Recharge::schema(),
+                );
+                components.schemas.insert(
+                    super::magic_items::Charges::name().to_string(),
+                    super::magic_items::Charges::schema(),
+                );
+                components.schemas.insert(
+                    super::magic_items::MagicItem::name().to_string(),
+                    super::magic_items::MagicItem::schema(),
+                );
+                components.schemas.insert(
+                    super::attacks::AttackMode::name().to_string(),
+                    super::attacks::AttackMode::schema(),
+                );
+                components.schemas.insert(
+                    super::attacks::Attack::name().to_string(),
+                    super::attacks::Attack::schema(),
+                );
+                components.schemas.insert(
+                    super::spells::SpellComponent::name().to_string(),
+                    super::spells::SpellComponent::schema(),
+                );
+                components.schemas.insert(
+                    super::spells::SpellAttackType::name().to_string(),
+                    super::spells::SpellAttackType::schema(),
+                );
+                components.schemas.insert(
+                    super::spells::AreaOfEffectShape::name().to_string(),
+                    super::spells::AreaOfEffectShape::schema(),
+                );
+                components.schemas.insert(
+                    super::spells::AreaOfEffect::name().to_string(),
+                    super::spells::AreaOfEffect::schema(),
+                );
+                components.schemas.insert(
+                    super::spells::DcSuccess::name().to_string(),
+                    super::spells::DcSuccess::schema(),
+                );
+                components.schemas.insert(
+                    super::spells::SpellDc::name().to_string(),
+                    super::spells::SpellDc::schema(),
+                );
+                components.schemas.insert(
+                    super::spells::SpellDetails::name().to_string(),
+                    super::spells::SpellDetails::schema(),
+                );
+                components.schemas.insert(
+                    super::spells::SpellFilter::name().to_string(),
+                    super::spells::SpellFilter::schema(),
+                );
+                components.schemas.insert(
+                    super::concentration::ActiveEffect::name().to_string(),
+                    super::concentration::ActiveEffect::schema(),
+                );
+                components.schemas.insert(
+                    super::concentration::ConcentrationCheck::name().to_string(),
+                    super::concentration::ConcentrationCheck::schema(),
+                );
+                components.schemas.insert(
+                    super::spells::CastingMode::name().to_string(),
+                    super::spells::CastingMode::schema(),
+                );
+                components.schemas.insert(
+                    super::hit_points::DeathSaves::name().to_string(),
+                    super::hit_points::DeathSaves::schema(),
+                );
+                components.schemas.insert(
+                    super::hit_points::HitPointEvent::name().to_string(),
+                    super::hit_points::HitPointEvent::schema(),
+                );
+                components.schemas.insert(
+                    super::resistances::DamageResistances::name().to_string(),
+                    super::resistances::DamageResistances::schema(),
+                );
+                components.schemas.insert(
+                    super::conditions::Condition::name().to_string(),
+                    super::conditions::Condition::schema(),
+                );
+                components.schemas.insert(
+                    super::conditions::ConditionDuration::name().to_string(),
+                    super::conditions::ConditionDuration::schema(),
+                );
                 components.schemas.insert(
                     super::Abilities::name().to_string(),
                     super::Abilities::schema(),
@@ -228,6 +498,26 @@ pub mod utoipa_addon {
                     super::abilities::AbilityScore::name().to_string(),
                     super::abilities::AbilityScore::schema(),
                 );
+                components.schemas.insert(
+                    super::abilities::AbilityScoreSource::name().to_string(),
+                    super::abilities::AbilityScoreSource::schema(),
+                );
+                components.schemas.insert(
+                    super::abilities::AbilityScoreChange::name().to_string(),
+                    super::abilities::AbilityScoreChange::schema(),
+                );
+                components.schemas.insert(
+                    super::abilities::AbilityScoreModifier::name().to_string(),
+                    super::abilities::AbilityScoreModifier::schema(),
+                );
+                components.schemas.insert(
+                    super::abilities::AbilityScoreContribution::name().to_string(),
+                    super::abilities::AbilityScoreContribution::schema(),
+                );
+                components.schemas.insert(
+                    super::abilities::AbilityScoreBreakdown::name().to_string(),
+                    super::abilities::AbilityScoreBreakdown::schema(),
+                );
                 components.schemas.insert(
                     super::Character::name().to_string(),
                     super::Character::schema(),
@@ -276,60 +566,23 @@ impl Character {
             background_index,
             background_description,
             experience_points: 0,
-            money: 0,
-            inventory: HashMap::new(),
+            money: Purse::default(),
+            inventory: vec![],
 
             abilities_score,
             hp: 0,
             hit_dice_result: 0,
+            temporary_hp: 0,
+            death_saves: DeathSaves::default(),
+            damage_resistances: DamageResistances::default(),
+            conditions: vec![],
+            exhaustion: 0,
+            active_effects: vec![],
+            feats: vec![],
             other: vec![],
         }
     }
 
-    pub fn class_armor(&self) -> i8 {
-        // Get the first class and its name
-        let first_class = self.classes.0.iter().next().unwrap();
-        let class_name = first_class.0.as_str();
-
-        let abilities_score = self.abilities_score.lock().unwrap();
-
-        // Calculate the base armor class based on the class type
-        let mut base = match class_name {
-            "monk" => {
-                10 + abilities_score.dexterity.modifier(0) + abilities_score.wisdom.modifier(0)
-            }
-            "sorcerer" => 13 + abilities_score.dexterity.modifier(0),
-            "barbarian" => {
-                10 + abilities_score.dexterity.modifier(0)
-                    + abilities_score.constitution.modifier(0)
-            }
-            _ => 10 + abilities_score.dexterity.modifier(0),
-        };
-
-        // Check if the character has the "Fighting Style: Defense" feature
-        let has_defense_style = first_class
-            .1
-            .1
-            .fighting_style
-            .as_ref()
-            .map(|s| s.contains("defense"))
-            .unwrap_or(false)
-            || first_class
-                .1
-                .1
-                .additional_fighting_style
-                .as_ref()
-                .map(|s| s.contains("defense"))
-                .unwrap_or(false);
-
-        // Add bonus if the character has the defense fighting style
-        if has_defense_style {
-            base += 1;
-        }
-
-        base
-    }
-
     /// Return current level of the character
     pub fn level(&self) -> u8 {
         LEVELS
@@ -339,6 +592,12 @@ impl Character {
             + 1
     }
 
+    /// Returns the proficiency bonus of the character, from its total level
+    /// https://www.dnd5eapi.co/api/rule-sections/proficiency-bonus
+    pub fn proficiency_bonus(&self) -> u8 {
+        2 + (self.level() - 1) / 4
+    }
+
     /// Returns the experience points of the character
     pub fn experience_points(&self) -> u32 {
         self.experience_points
@@ -369,34 +628,6 @@ impl Character {
         current_level - previous_level
     }
 
-    pub fn remove_item(
-        &mut self,
-        item: &str,
-        amount: Option<u16>,
-    ) -> anyhow::Result<(), anyhow::Error> {
-        if let Some(quantity) = self.inventory.get_mut(item) {
-            let quantity_to_remove = amount.unwrap_or(*quantity);
-
-            if *quantity <= quantity_to_remove {
-                self.inventory.remove(item);
-            } else {
-                *quantity -= quantity_to_remove;
-            }
-        } else {
-            bail!("Item not found")
-        }
-
-        Ok(())
-    }
-
-    pub fn add_item(&mut self, item: &str, amount: u16) {
-        if let Some(quantity) = self.inventory.get_mut(item) {
-            *quantity += amount;
-        } else {
-            self.inventory.insert(item.to_string(), amount);
-        }
-    }
-
     pub fn alter_item_quantity(&mut self, item: &str, amount: i32) -> anyhow::Result<()> {
         match amount.cmp(&0) {
             Ordering::Greater => {
@@ -410,7 +641,66 @@ impl Character {
         }
     }
 
+    /// Call this method after a short rest to restore the class resources that recover with it
+    pub fn short_rest(&mut self) {
+        self.classes.rest(RestType::Short);
+        self.recharge_magic_items(Recharge::ShortRest);
+        self.end_rest_conditions(RestType::Short);
+    }
+
+    /// Call this method after a short rest, recovering the given spell slots with the
+    /// Arcane Recovery of the wizard class
+    /// Nothing is restored if the slots can't be recovered
+    /// # Arguments
+    /// * `slots` - Level of each slot to recover
+    /// * `max_slots` - Slots of the wizard at its current level
+    pub fn short_rest_recovering_slots(
+        &mut self,
+        slots: &[u8],
+        max_slots: &UsableSlots,
+    ) -> anyhow::Result<()> {
+        let Some(wizard) = self.classes.0.get("wizard") else {
+            bail!("The character doesn't have the wizard class")
+        };
+        wizard.1.check_arcane_recovery(slots, max_slots)?;
+
+        self.short_rest();
+
+        let Some(wizard) = self.classes.0.get_mut("wizard") else {
+            bail!("The character doesn't have the wizard class")
+        };
+        wizard.1.arcane_recovery(slots, max_slots)
+    }
+
+    /// Returns true if the character has the feat, from any source
+    /// # Arguments
+    /// * `feat` - Index from https://www.dnd5eapi.co/api/feats/
+    pub fn has_feat(&self, feat: &str) -> bool {
+        self.feats.iter().any(|f| f == feat)
+    }
+
+    /// Returns the modifier of a saving throw, with the proficiency bonus if the character
+    /// is proficient and the bonus of the magic items
+    /// # Arguments
+    /// * `ability` - Index from https://www.dnd5eapi.co/api/ability-scores/, e.g. `dex`
+    pub fn saving_throw(&self, ability: &str) -> anyhow::Result<i8> {
+        let proficiency_bonus = self.proficiency_bonus();
+
+        let Some(modifier) = self
+            .abilities_score
+            .lock()
+            .unwrap()
+            .get(ability)
+            .map(|ability| ability.modifier(proficiency_bonus))
+        else {
+            bail!("Unknown ability {}", ability)
+        };
+
+        Ok(modifier + self.magic_item_saving_throw_bonus())
+    }
+
     /// Calculate the maximum HP of the character based on constitution modifier and hit dice result
+    /// It is halved from the 4th level of exhaustion
     pub fn max_hp(&self) -> u16 {
         let constitution_modifier = self
             .abilities_score
@@ -419,9 +709,15 @@ impl Character {
             .constitution
             .modifier(0);
 
-        (constitution_modifier as i32)
+        let max_hp = (constitution_modifier as i32 + self.hp_bonus_per_level() as i32)
             .saturating_mul(self.level().into())
             .saturating_add(self.hit_dice_result.into())
-            .max(0) as u16
+            .max(0) as u16;
+
+        if self.exhaustion >= 4 {
+            max_hp / 2
+        } else {
+            max_hp
+        }
     }
 }
@@ -0,0 +1,357 @@
+use crate::Character;
+use crate::abilities::{ABILITY_NAMES, AbilityScoreChange, AbilityScoreSource};
+use crate::equipment::{Item, ItemState};
+use anyhow::bail;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of magic items a character can be attuned to at the same time
+pub const MAX_ATTUNED_ITEMS: usize = 3;
+
+/// https://www.dnd5eapi.co/api/magic-items/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum Rarity {
+    /// The rarity depends on the variant, e.g. a +1 or a +3 weapon
+    #[default]
+    Varies,
+    Common,
+    Uncommon,
+    Rare,
+    VeryRare,
+    Legendary,
+    Artifact,
+}
+
+/// What the item does while it is equipped, and attuned if it requires attunement
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum MagicItemEffect {
+    /// e.g. https://www.dnd5eapi.co/api/magic-items/ring-of-protection
+    ArmorClassBonus(i8),
+    /// Bonus to every saving throw, e.g. https://www.dnd5eapi.co/api/magic-items/cloak-of-protection
+    SavingThrowBonus(i8),
+    /// Bonus to attack rolls, only with the item itself if it is a weapon
+    AttackBonus(i8),
+    /// e.g. https://www.dnd5eapi.co/api/magic-items/gauntlets-of-ogre-power
+    SetAbilityScore {
+        /// Index from https://www.dnd5eapi.co/api/ability-scores/
+        ability: String,
+        score: u8,
+    },
+    /// A charge can be spent to regain a spell slot of the level,
+    /// e.g. https://www.dnd5eapi.co/api/magic-items/pearl-of-power
+    SpellSlot(u8),
+    /// Index from https://www.dnd5eapi.co/api/damage-types/,
+    /// e.g. https://www.dnd5eapi.co/api/magic-items/ring-of-resistance
+    DamageResistance(String),
+    /// Index from https://www.dnd5eapi.co/api/damage-types/,
+    /// e.g. https://www.dnd5eapi.co/api/magic-items/periapt-of-proof-against-poison
+    DamageImmunity(String),
+}
+
+/// When the expended charges of an item are regained
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum Recharge {
+    ShortRest,
+    /// Called with Character::new_day
+    Dawn,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct Charges {
+    pub max: u16,
+    pub used: u16,
+    /// None for items that never regain their charges
+    pub recharge: Option<Recharge>,
+}
+
+impl Charges {
+    pub fn remaining(&self) -> u16 {
+        self.max.saturating_sub(self.used)
+    }
+}
+
+/// Magic properties of an item
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct MagicItem {
+    pub rarity: Rarity,
+    pub requires_attunement: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub attuned: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub effects: Vec<MagicItemEffect>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub charges: Option<Charges>,
+}
+
+impl Item {
+    /// Returns true if the effects of the item apply, it must be equipped
+    /// and attuned if it requires attunement
+    pub fn magic_active(&self) -> bool {
+        self.state == ItemState::Equipped
+            && self
+                .magic
+                .as_ref()
+                .is_some_and(|magic| magic.attuned || !magic.requires_attunement)
+    }
+
+    /// Returns the effects of the item if they apply
+    pub fn active_effects(&self) -> impl Iterator<Item = &MagicItemEffect> {
+        self.magic
+            .as_ref()
+            .filter(|_| self.magic_active())
+            .into_iter()
+            .flat_map(|magic| magic.effects.iter())
+    }
+}
+
+impl Character {
+    /// Returns the items the character is attuned to
+    pub fn attuned_items(&self) -> impl Iterator<Item = &Item> {
+        self.inventory
+            .iter()
+            .filter(|item| item.magic.as_ref().is_some_and(|magic| magic.attuned))
+    }
+
+    /// Attunes the character to one unit of the magic item
+    /// Returns an error if the item doesn't require attunement or if the character
+    /// is already attuned to 3 items
+    /// # Arguments
+    /// * `index` - Index from https://www.dnd5eapi.co/api/magic-items/
+    pub fn attune(&mut self, index: &str) -> anyhow::Result<()> {
+        let attuned: usize = self
+            .attuned_items()
+            .map(|item| item.quantity as usize)
+            .sum();
+        if attuned >= MAX_ATTUNED_ITEMS {
+            bail!(
+                "Can't be attuned to more than {} items at once",
+                MAX_ATTUNED_ITEMS
+            )
+        }
+
+        let Some(position) = self.inventory.iter().position(|item| {
+            item.index == index
+                && item
+                    .magic
+                    .as_ref()
+                    .is_some_and(|magic| magic.requires_attunement && !magic.attuned)
+        }) else {
+            bail!("No {} to attune to in the inventory", index)
+        };
+
+        let stack = &mut self.inventory[position];
+        let mut item = stack.clone();
+        item.quantity = 1;
+        if let Some(magic) = item.magic.as_mut() {
+            magic.attuned = true;
+        }
+
+        stack.quantity -= 1;
+        if stack.quantity == 0 {
+            self.inventory.remove(position);
+        }
+
+        self.insert_item(item);
+
+        Ok(())
+    }
+
+    /// Ends the attunement to one unit of the magic item
+    /// # Arguments
+    /// * `index` - Index from https://www.dnd5eapi.co/api/magic-items/
+    pub fn end_attunement(&mut self, index: &str) -> anyhow::Result<()> {
+        let Some(position) = self.inventory.iter().position(|item| {
+            item.index == index && item.magic.as_ref().is_some_and(|magic| magic.attuned)
+        }) else {
+            bail!("Not attuned to {}", index)
+        };
+
+        let stack = &mut self.inventory[position];
+        let mut item = stack.clone();
+        item.quantity = 1;
+        if let Some(magic) = item.magic.as_mut() {
+            magic.attuned = false;
+        }
+
+        stack.quantity -= 1;
+        if stack.quantity == 0 {
+            self.inventory.remove(position);
+        }
+
+        self.insert_item(item);
+
+        Ok(())
+    }
+
+    /// Returns the active effects of all the magic items
+    pub fn magic_item_effects(&self) -> impl Iterator<Item = &MagicItemEffect> {
+        self.inventory.iter().flat_map(Item::active_effects)
+    }
+
+    /// Returns the bonus to the armor class of the active magic items
+    pub fn magic_item_armor_class_bonus(&self) -> i8 {
+        self.magic_item_effects()
+            .map(|effect| match effect {
+                MagicItemEffect::ArmorClassBonus(bonus) => *bonus,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Returns the bonus to the saving throws of the active magic items
+    pub fn magic_item_saving_throw_bonus(&self) -> i8 {
+        self.magic_item_effects()
+            .map(|effect| match effect {
+                MagicItemEffect::SavingThrowBonus(bonus) => *bonus,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Returns the bonus to the attack rolls of the active magic items,
+    /// the bonus of a magic weapon only counts when attacking with it
+    /// # Arguments
+    /// * `weapon` - The item used to attack, None for unarmed strikes and spells
+    pub fn magic_item_attack_bonus(&self, weapon: Option<&Item>) -> i8 {
+        self.inventory
+            .iter()
+            .filter(|item| item.weapon().is_none() || weapon == Some(*item))
+            .flat_map(Item::active_effects)
+            .map(|effect| match effect {
+                MagicItemEffect::AttackBonus(bonus) => *bonus,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Spends charges of a magic item
+    /// Returns an error without spending anything if there are not enough charges
+    /// # Arguments
+    /// * `index` - Index from https://www.dnd5eapi.co/api/magic-items/
+    pub fn use_charges(&mut self, index: &str, amount: u16) -> anyhow::Result<()> {
+        let Some(charges) = self
+            .inventory
+            .iter_mut()
+            .filter(|item| item.index == index)
+            .filter_map(|item| item.magic.as_mut()?.charges.as_mut())
+            .find(|charges| charges.remaining() >= amount)
+        else {
+            bail!("Not enough charges left in {}", index)
+        };
+
+        charges.used += amount;
+
+        Ok(())
+    }
+
+    /// Spends a charge of the magic item to regain a spell slot of the class
+    /// # Arguments
+    /// * `index` - Index from https://www.dnd5eapi.co/api/magic-items/
+    /// * `class` - Index from https://www.dnd5eapi.co/api/classes/
+    pub fn regain_spell_slot_with_item(&mut self, index: &str, class: &str) -> anyhow::Result<()> {
+        let Some(level) = self
+            .inventory
+            .iter()
+            .filter(|item| item.index == index)
+            .flat_map(Item::active_effects)
+            .find_map(|effect| match effect {
+                MagicItemEffect::SpellSlot(level) => Some(*level),
+                _ => None,
+            })
+        else {
+            bail!("{} can't restore spell slots", index)
+        };
+
+        if !(1..=9).contains(&level) {
+            bail!("Invalid spell slot level {}", level)
+        }
+
+        if self
+            .classes
+            .0
+            .get(class)
+            .and_then(|class| class.1.usable_slots())
+            .is_none()
+        {
+            bail!("This class doesn't have spell slots")
+        }
+
+        self.use_charges(index, 1)?;
+
+        if let Some(slots) = self
+            .classes
+            .0
+            .get_mut(class)
+            .and_then(|class| class.1.usable_slots_mut())
+            .and_then(|usable_slots| usable_slots.slots_mut(level))
+        {
+            *slots += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Regains the charges of the magic items that recharge with the rest,
+    /// a long rest happens with the dawn so it restores every rechargeable item
+    pub(crate) fn recharge_magic_items(&mut self, recharge: Recharge) {
+        self.inventory
+            .iter_mut()
+            .filter_map(|item| item.magic.as_mut()?.charges.as_mut())
+            .filter(|charges| match charges.recharge {
+                Some(Recharge::ShortRest) => true,
+                Some(Recharge::Dawn) => recharge == Recharge::Dawn,
+                None => false,
+            })
+            .for_each(|charges| charges.used = 0);
+    }
+
+    /// Updates the ability scores set by the active magic items,
+    /// called every time the inventory changes
+    pub(crate) fn update_magic_item_modifiers(&mut self) {
+        let scores: Vec<(AbilityScoreSource, String, u8)> = self
+            .inventory
+            .iter()
+            .flat_map(|item| {
+                item.active_effects().filter_map(|effect| match effect {
+                    MagicItemEffect::SetAbilityScore { ability, score } => Some((
+                        AbilityScoreSource::MagicItem(item.index.clone()),
+                        ability.clone(),
+                        *score,
+                    )),
+                    _ => None,
+                })
+            })
+            .collect();
+
+        let mut abilities = self.abilities_score.lock().unwrap();
+
+        for ability in ABILITY_NAMES {
+            if let Some(ability) = abilities.get_mut(ability) {
+                ability.modifiers.retain(|modifier| {
+                    !matches!(modifier.source, AbilityScoreSource::MagicItem(_))
+                });
+            }
+        }
+
+        for (source, ability, score) in scores {
+            if let Some(ability) = abilities.get_mut(&ability) {
+                ability.add_modifier(source, AbilityScoreChange::Set(score));
+            }
+        }
+    }
+}
@@ -0,0 +1,279 @@
+use crate::Character;
+use crate::equipment::Item;
+use anyhow::bail;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Coin denominations
+/// https://www.dnd5eapi.co/api/rule-sections/coins
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum Currency {
+    Cp,
+    Sp,
+    Ep,
+    Gp,
+    Pp,
+}
+
+impl Currency {
+    /// All the denominations, from the smallest to the largest
+    pub const ALL: [Currency; 5] = [
+        Currency::Cp,
+        Currency::Sp,
+        Currency::Ep,
+        Currency::Gp,
+        Currency::Pp,
+    ];
+
+    /// Returns how many copper pieces a coin is worth
+    pub fn copper_value(&self) -> u64 {
+        match self {
+            Currency::Cp => 1,
+            Currency::Sp => 10,
+            Currency::Ep => 50,
+            Currency::Gp => 100,
+            Currency::Pp => 1000,
+        }
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let unit = match self {
+            Currency::Cp => "cp",
+            Currency::Sp => "sp",
+            Currency::Ep => "ep",
+            Currency::Gp => "gp",
+            Currency::Pp => "pp",
+        };
+
+        write!(f, "{}", unit)
+    }
+}
+
+impl FromStr for Currency {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "cp" => Ok(Currency::Cp),
+            "sp" => Ok(Currency::Sp),
+            "ep" => Ok(Currency::Ep),
+            "gp" => Ok(Currency::Gp),
+            "pp" => Ok(Currency::Pp),
+            _ => bail!("Unknown currency {}", s),
+        }
+    }
+}
+
+/// A price, like the cost of the equipment in the api
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct Cost {
+    pub quantity: u32,
+    pub unit: Currency,
+}
+
+impl Cost {
+    pub fn in_copper(&self) -> u64 {
+        self.quantity as u64 * self.unit.copper_value()
+    }
+}
+
+impl fmt::Display for Cost {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.quantity, self.unit)
+    }
+}
+
+/// Parses prices like "15 gp" or "2sp"
+impl FromStr for Cost {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let (quantity, unit) = s.split_at(split);
+
+        let Ok(quantity) = quantity.parse() else {
+            bail!("Invalid price {}", s)
+        };
+
+        Ok(Cost {
+            quantity,
+            unit: unit.parse()?,
+        })
+    }
+}
+
+/// The coins of the character
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct Purse {
+    pub cp: u32,
+    pub sp: u32,
+    pub ep: u32,
+    pub gp: u32,
+    pub pp: u32,
+}
+
+impl Purse {
+    pub fn coins(&self, currency: Currency) -> u32 {
+        match currency {
+            Currency::Cp => self.cp,
+            Currency::Sp => self.sp,
+            Currency::Ep => self.ep,
+            Currency::Gp => self.gp,
+            Currency::Pp => self.pp,
+        }
+    }
+
+    pub fn coins_mut(&mut self, currency: Currency) -> &mut u32 {
+        match currency {
+            Currency::Cp => &mut self.cp,
+            Currency::Sp => &mut self.sp,
+            Currency::Ep => &mut self.ep,
+            Currency::Gp => &mut self.gp,
+            Currency::Pp => &mut self.pp,
+        }
+    }
+
+    /// Returns the value of all the coins in copper pieces
+    pub fn total_in_copper(&self) -> u64 {
+        Currency::ALL
+            .iter()
+            .map(|currency| self.coins(*currency) as u64 * currency.copper_value())
+            .sum()
+    }
+
+    pub fn add(&mut self, cost: Cost) {
+        let coins = self.coins_mut(cost.unit);
+        *coins = coins.saturating_add(cost.quantity);
+    }
+
+    /// Adds the value in gold, silver and copper pieces
+    pub fn add_copper(&mut self, copper: u64) {
+        let mut remaining = copper;
+
+        for currency in [Currency::Gp, Currency::Sp, Currency::Cp] {
+            let coins = remaining / currency.copper_value();
+            remaining -= coins * currency.copper_value();
+
+            let purse_coins = self.coins_mut(currency);
+            *purse_coins = purse_coins.saturating_add(coins.min(u32::MAX as u64) as u32);
+        }
+    }
+
+    /// Pays the price, using the smallest coins first and taking the change
+    /// in gold, silver and copper pieces
+    /// Returns an error without taking any coin if there is not enough money
+    pub fn pay(&mut self, cost: Cost) -> anyhow::Result<()> {
+        let price = cost.in_copper();
+
+        if self.total_in_copper() < price {
+            bail!("Not enough money to pay {}", cost)
+        }
+
+        let mut paid = 0;
+        for currency in Currency::ALL {
+            if paid >= price {
+                break;
+            }
+
+            let value = currency.copper_value();
+            let coins = self.coins_mut(currency);
+            let needed = (price - paid).div_ceil(value);
+            let used = needed.min(*coins as u64);
+
+            *coins -= used as u32;
+            paid += used * value;
+        }
+
+        self.add_copper(paid - price);
+
+        Ok(())
+    }
+
+    /// Exchanges coins of one denomination for another one of the same value
+    /// Returns an error if there are not enough coins or if the value can't be
+    /// exchanged without a remainder
+    pub fn exchange(&mut self, from: Currency, to: Currency, amount: u32) -> anyhow::Result<()> {
+        if self.coins(from) < amount {
+            bail!("Not enough {}", from)
+        }
+
+        let value = amount as u64 * from.copper_value();
+        if !value.is_multiple_of(to.copper_value()) {
+            bail!(
+                "{} {} can't be exchanged for {} without a remainder",
+                amount,
+                from,
+                to
+            )
+        }
+
+        let exchanged = value / to.copper_value();
+        if exchanged > (u32::MAX - self.coins(to)) as u64 {
+            bail!("Too many {}", to)
+        }
+
+        *self.coins_mut(from) -= amount;
+        *self.coins_mut(to) += exchanged as u32;
+
+        Ok(())
+    }
+}
+
+impl Character {
+    /// Buys the items, paying their cost and adding them to the inventory
+    /// Returns an error without changing anything if the item has no cost or
+    /// there is not enough money
+    pub fn buy(&mut self, item: Item) -> anyhow::Result<()> {
+        let Some(cost) = item.cost else {
+            bail!("{} has no price", item.name)
+        };
+
+        self.money.pay(Cost {
+            quantity: cost.quantity.saturating_mul(item.quantity as u32),
+            unit: cost.unit,
+        })?;
+        self.insert_item(item);
+
+        Ok(())
+    }
+
+    /// Sells the items for half their cost, removing them from the inventory
+    /// Returns the money received in copper pieces
+    /// Returns an error without changing anything if there are not enough items
+    /// # Arguments
+    /// * `index` - Index from https://www.dnd5eapi.co/api/equipment/
+    /// * `quantity` - Number of items to sell
+    pub fn sell(&mut self, index: &str, quantity: u16) -> anyhow::Result<u64> {
+        let items = self.inventory.iter().filter(|item| item.index == index);
+
+        let owned: u32 = items.clone().map(|item| item.quantity as u32).sum();
+        if owned < quantity as u32 {
+            bail!("Not enough {} to sell", index)
+        }
+
+        let Some(cost) = items.filter_map(|item| item.cost).next() else {
+            bail!("{} has no price", index)
+        };
+
+        let price = cost.in_copper() * quantity as u64 / 2;
+
+        self.remove_item(index, Some(quantity))?;
+        self.money.add_copper(price);
+
+        Ok(price)
+    }
+}
@@ -0,0 +1,113 @@
+use crate::Character;
+use crate::magic_items::MagicItemEffect;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Indexes from https://www.dnd5eapi.co/api/damage-types/ that change the damage taken
+/// https://www.dnd5eapi.co/api/rule-sections/damage-and-healing
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase", default))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct DamageResistances {
+    /// The damage is halved
+    pub resistances: BTreeSet<String>,
+    /// No damage is taken
+    pub immunities: BTreeSet<String>,
+    /// The damage is doubled
+    pub vulnerabilities: BTreeSet<String>,
+}
+
+impl DamageResistances {
+    /// Returns the damage after the immunity, resistance and vulnerability to its type,
+    /// several resistances or vulnerabilities to the same type count as one
+    /// # Arguments
+    /// * `damage_type` - Index from https://www.dnd5eapi.co/api/damage-types/
+    pub fn apply(&self, damage: u16, damage_type: &str) -> u16 {
+        if self.immunities.contains(damage_type) {
+            return 0;
+        }
+
+        let mut damage = damage;
+
+        if self.resistances.contains(damage_type) {
+            damage /= 2;
+        }
+
+        if self.vulnerabilities.contains(damage_type) {
+            damage = damage.saturating_mul(2);
+        }
+
+        damage
+    }
+
+    /// Adds the damage types of the other set
+    pub fn extend(&mut self, other: &DamageResistances) {
+        self.resistances.extend(other.resistances.iter().cloned());
+        self.immunities.extend(other.immunities.iter().cloned());
+        self.vulnerabilities
+            .extend(other.vulnerabilities.iter().cloned());
+    }
+}
+
+/// Returns the damage type of a dragon ancestor of the draconic bloodline,
+/// e.g. fire for dragon-ancestor-red---fire-damage
+/// https://www.dnd5eapi.co/api/features/dragon-ancestor
+pub fn dragon_ancestor_damage_type(ancestor: &str) -> Option<&str> {
+    ancestor.split("---").nth(1)?.strip_suffix("-damage")
+}
+
+impl Character {
+    /// Returns the resistances, immunities and vulnerabilities of the character from its race,
+    /// its class features, its active magic items and Character::damage_resistances
+    pub fn effective_damage_resistances(&self) -> DamageResistances {
+        let mut resistances = self.damage_resistances.clone();
+
+        match self.race_index.as_str() {
+            // https://www.dnd5eapi.co/api/traits/dwarven-resilience
+            "dwarf" => resistances.resistances.insert("poison".to_string()),
+            // https://www.dnd5eapi.co/api/traits/hellish-resistance
+            "tiefling" => resistances.resistances.insert("fire".to_string()),
+            // The resistance of dragonborns depends on their draconic ancestry which is not
+            // stored, it must be added to Character::damage_resistances
+            _ => false,
+        };
+
+        for class in self.classes.0.values() {
+            // https://www.dnd5eapi.co/api/features/rage
+            if class.1.raging {
+                for damage_type in ["bludgeoning", "piercing", "slashing"] {
+                    resistances.resistances.insert(damage_type.to_string());
+                }
+            }
+
+            // Draconic sorcerers resist the damage type of their ancestor from the 18th level
+            if class.index() == "sorcerer"
+                && class.1.subclass.as_deref() == Some("draconic")
+                && class.1.level >= 18
+                && let Some(damage_type) = class
+                    .1
+                    .sorcerer_dragon_ancestor
+                    .as_deref()
+                    .and_then(dragon_ancestor_damage_type)
+            {
+                resistances.resistances.insert(damage_type.to_string());
+            }
+        }
+
+        for effect in self.magic_item_effects() {
+            match effect {
+                MagicItemEffect::DamageResistance(damage_type) => {
+                    resistances.resistances.insert(damage_type.clone());
+                }
+                MagicItemEffect::DamageImmunity(damage_type) => {
+                    resistances.immunities.insert(damage_type.clone());
+                }
+                _ => {}
+            }
+        }
+
+        resistances
+    }
+}
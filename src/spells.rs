@@ -0,0 +1,277 @@
+use crate::Character;
+use crate::concentration::ActiveEffect;
+use anyhow::bail;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum SpellComponent {
+    Verbal,
+    Somatic,
+    Material,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum SpellAttackType {
+    Melee,
+    Ranged,
+}
+
+/// https://www.dnd5eapi.co/api/rule-sections/areas-of-effect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum AreaOfEffectShape {
+    Sphere,
+    Cube,
+    Cylinder,
+    Line,
+    Cone,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct AreaOfEffect {
+    pub shape: AreaOfEffectShape,
+    /// Size in feet, e.g. the radius of a sphere
+    pub size: u16,
+}
+
+/// What happens when the target succeeds the saving throw
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum DcSuccess {
+    /// No effect
+    None,
+    /// Half damage
+    Half,
+    /// Described in SpellDc::desc
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct SpellDc {
+    /// Index from https://www.dnd5eapi.co/api/ability-scores/ of the saving throw
+    pub ability: String,
+    pub success: DcSuccess,
+    pub desc: Option<String>,
+}
+
+/// https://www.dnd5eapi.co/api/spells/
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct SpellDetails {
+    /// Index from https://www.dnd5eapi.co/api/spells/
+    pub index: String,
+    pub name: String,
+    /// 0 for cantrips
+    pub level: u8,
+    /// Index from https://www.dnd5eapi.co/api/magic-schools/
+    pub school: String,
+    /// e.g. 1 action
+    pub casting_time: String,
+    /// e.g. 120 feet
+    pub range: String,
+    pub components: Vec<SpellComponent>,
+    /// The material component, e.g. a tiny ball of bat guano and sulfur
+    pub material: Option<String>,
+    /// e.g. Up to 1 minute
+    pub duration: String,
+    pub concentration: bool,
+    pub ritual: bool,
+    pub attack_type: Option<SpellAttackType>,
+    /// Index from https://www.dnd5eapi.co/api/damage-types/
+    pub damage_type: Option<String>,
+    /// Damage by the level of the slot used, e.g. 3: 8d6 for fireball
+    pub damage_at_slot_level: BTreeMap<u8, String>,
+    /// Damage of cantrips by the level of the character, e.g. 5: 2d10 for fire bolt
+    pub damage_at_character_level: BTreeMap<u8, String>,
+    /// Healing by the level of the slot used, e.g. 1: 1d8 + MOD for cure wounds
+    pub heal_at_slot_level: BTreeMap<u8, String>,
+    pub dc: Option<SpellDc>,
+    pub area_of_effect: Option<AreaOfEffect>,
+    /// Indexes from https://www.dnd5eapi.co/api/classes/
+    pub classes: Vec<String>,
+    /// Indexes from https://www.dnd5eapi.co/api/subclasses/
+    pub subclasses: Vec<String>,
+    pub desc: Vec<String>,
+    pub higher_level: Vec<String>,
+}
+
+/// Returns the value of the highest level that is not above the given one
+fn at_level(values: &BTreeMap<u8, String>, level: u8) -> Option<&str> {
+    values
+        .range(..=level)
+        .next_back()
+        .map(|(_, value)| value.as_str())
+}
+
+impl SpellDetails {
+    /// Returns the damage when cast with a slot of the level
+    pub fn damage_at_slot(&self, slot_level: u8) -> Option<&str> {
+        at_level(&self.damage_at_slot_level, slot_level)
+    }
+
+    /// Returns the damage of a cantrip cast by a character of the level
+    pub fn damage_at_character_level(&self, character_level: u8) -> Option<&str> {
+        at_level(&self.damage_at_character_level, character_level)
+    }
+
+    /// Returns the healing when cast with a slot of the level
+    pub fn heal_at_slot(&self, slot_level: u8) -> Option<&str> {
+        at_level(&self.heal_at_slot_level, slot_level)
+    }
+}
+
+/// Filter for the spells, every field set must match
+/// Used with the spells query of the api or with SpellFilter::matches
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase", default))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct SpellFilter {
+    /// Index from https://www.dnd5eapi.co/api/classes/
+    pub class: Option<String>,
+    /// Index from https://www.dnd5eapi.co/api/subclasses/
+    pub subclass: Option<String>,
+    /// Index from https://www.dnd5eapi.co/api/magic-schools/
+    pub school: Option<String>,
+    /// Any of the levels, all levels if empty
+    pub levels: Vec<u8>,
+    pub concentration: Option<bool>,
+    pub ritual: Option<bool>,
+    pub attack_type: Option<SpellAttackType>,
+    pub casting_time: Option<String>,
+    pub area_of_effect: Option<AreaOfEffectShape>,
+    /// Index from https://www.dnd5eapi.co/api/damage-types/
+    pub damage_type: Option<String>,
+    /// Index from https://www.dnd5eapi.co/api/ability-scores/ of the saving throw
+    pub dc_type: Option<String>,
+    pub range: Option<String>,
+    /// Part of the name, case insensitive
+    pub name: Option<String>,
+}
+
+impl SpellFilter {
+    /// Returns true if the spell matches every field of the filter
+    pub fn matches(&self, spell: &SpellDetails) -> bool {
+        fn check<T>(filter: &Option<T>, matches: impl FnOnce(&T) -> bool) -> bool {
+            filter.as_ref().is_none_or(matches)
+        }
+
+        check(&self.class, |class| spell.classes.contains(class))
+            && check(&self.subclass, |subclass| {
+                spell.subclasses.contains(subclass)
+            })
+            && check(&self.school, |school| &spell.school == school)
+            && (self.levels.is_empty() || self.levels.contains(&spell.level))
+            && check(&self.concentration, |c| spell.concentration == *c)
+            && check(&self.ritual, |ritual| spell.ritual == *ritual)
+            && check(&self.attack_type, |a| spell.attack_type == Some(*a))
+            && check(&self.casting_time, |c| &spell.casting_time == c)
+            && check(&self.area_of_effect, |shape| {
+                spell
+                    .area_of_effect
+                    .is_some_and(|area| area.shape == *shape)
+            })
+            && check(&self.damage_type, |d| spell.damage_type.as_ref() == Some(d))
+            && check(&self.dc_type, |dc_type| {
+                spell.dc.as_ref().is_some_and(|dc| &dc.ability == dc_type)
+            })
+            && check(&self.range, |range| &spell.range == range)
+            && check(&self.name, |name| {
+                spell.name.to_lowercase().contains(&name.to_lowercase())
+            })
+    }
+}
+
+/// How a spell is cast
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum CastingMode {
+    /// Uses a spell slot of the level, 0 for cantrips
+    Slot {
+        level: u8,
+        /// Indexes of the known metamagic applied to the spell
+        #[cfg_attr(feature = "serde", serde(default))]
+        metamagic: Vec<String>,
+    },
+    /// Takes 10 minutes more than the casting time and uses no spell slot,
+    /// see Class::can_cast_as_ritual
+    Ritual,
+}
+
+impl Character {
+    /// Casts the spell with the class, spending the spell slot and the sorcery points
+    /// of the casting mode, and starts its effect, see Character::start_spell_effect
+    /// Returns the effect of the spell the character stopped concentrating on
+    /// # Arguments
+    /// * `class` - Index from https://www.dnd5eapi.co/api/classes/
+    /// * `spell` - The spell cast, see api::spells::get_spell_details
+    pub fn cast_spell(
+        &mut self,
+        class: &str,
+        spell: &SpellDetails,
+        mode: &CastingMode,
+    ) -> anyhow::Result<Option<ActiveEffect>> {
+        let Some(class) = self.classes.0.get_mut(class) else {
+            bail!("The character doesn't have the class {}", class)
+        };
+
+        let slot_level = match mode {
+            CastingMode::Slot { level, metamagic } => {
+                if *level < spell.level || (spell.level == 0 && *level > 0) {
+                    bail!(
+                        "{} can't be cast with a slot of level {}",
+                        spell.name,
+                        level
+                    )
+                }
+
+                let metamagic: Vec<&str> = metamagic.iter().map(String::as_str).collect();
+                class.1.cast_spell(*level, &metamagic)?;
+
+                *level
+            }
+            CastingMode::Ritual => {
+                if !class.can_cast_as_ritual(spell) {
+                    bail!("{} can't be cast as a ritual", spell.name)
+                }
+
+                spell.level
+            }
+        };
+
+        Ok(self.start_spell_effect(spell, slot_level))
+    }
+
+    /// Returns the damage of the cantrip cast by the character, it increases with the
+    /// total level of the character at levels 5, 11 and 17, not with the level of the class
+    /// None if the spell is not a cantrip or doesn't deal damage
+    pub fn cantrip_damage<'a>(&self, spell: &'a SpellDetails) -> Option<&'a str> {
+        if spell.level != 0 {
+            return None;
+        }
+
+        spell.damage_at_character_level(self.level())
+    }
+}
@@ -0,0 +1,190 @@
+use crate::Character;
+use anyhow::bail;
+use rand::Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ItemQuantity {
+    /// Index from https://www.dnd5eapi.co/api/equipment/
+    pub index: String,
+    pub quantity: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum StartingEquipmentOption {
+    Item(ItemQuantity),
+    /// Pick items from an equipment category, e.g. any martial weapon
+    Category {
+        choose: u8,
+        /// Index from https://www.dnd5eapi.co/api/equipment-categories/
+        category: String,
+        /// Indexes from https://www.dnd5eapi.co/api/equipment/ in the category
+        items: Vec<String>,
+    },
+    /// All the options together, e.g. a leather armor, a longbow and 20 arrows
+    #[cfg_attr(feature = "utoipa", schema(no_recursion))]
+    Multiple(Vec<StartingEquipmentOption>),
+}
+
+impl StartingEquipmentOption {
+    /// Adds the items of the option, taking the picks of its categories in order
+    fn resolve<'a>(
+        &self,
+        picks: &mut impl Iterator<Item = &'a String>,
+        items: &mut Vec<ItemQuantity>,
+    ) -> anyhow::Result<()> {
+        match self {
+            StartingEquipmentOption::Item(item) => items.push(item.clone()),
+            StartingEquipmentOption::Category {
+                choose,
+                category,
+                items: category_items,
+            } => {
+                for _ in 0..*choose {
+                    let Some(pick) = picks.next() else {
+                        bail!("Missing a pick from {}", category)
+                    };
+
+                    if !category_items.contains(pick) {
+                        bail!("{} is not in {}", pick, category)
+                    }
+
+                    items.push(ItemQuantity {
+                        index: pick.clone(),
+                        quantity: 1,
+                    });
+                }
+            }
+            StartingEquipmentOption::Multiple(options) => {
+                for option in options {
+                    option.resolve(picks, items)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct StartingEquipmentChoice {
+    pub desc: String,
+    /// How many options must be chosen
+    pub choose: u8,
+    pub options: Vec<StartingEquipmentOption>,
+}
+
+/// The option chosen in a starting equipment choice
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct StartingEquipmentSelection {
+    /// Position of the option in the choice
+    pub option: usize,
+    /// Indexes from https://www.dnd5eapi.co/api/equipment/ picked for the categories
+    /// of the option, in the order of the categories
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub picks: Vec<String>,
+}
+
+/// Starting equipment of a class or a background
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct StartingEquipment {
+    /// Items always given
+    pub items: Vec<ItemQuantity>,
+    /// Choices pending for the user
+    pub choices: Vec<StartingEquipmentChoice>,
+}
+
+impl StartingEquipment {
+    /// Validates the selection and returns all the items of the starting equipment
+    /// # Arguments
+    /// * `selections` - The selected options of every choice, in the order of the choices
+    pub fn resolve(
+        &self,
+        selections: &[Vec<StartingEquipmentSelection>],
+    ) -> anyhow::Result<Vec<ItemQuantity>> {
+        if selections.len() != self.choices.len() {
+            bail!(
+                "Expected selections for {} choices, got {}",
+                self.choices.len(),
+                selections.len()
+            )
+        }
+
+        let mut items = self.items.clone();
+
+        for (choice, selection) in self.choices.iter().zip(selections) {
+            if selection.len() != choice.choose as usize {
+                bail!("{} needs {} options", choice.desc, choice.choose)
+            }
+
+            for selected in selection {
+                let Some(option) = choice.options.get(selected.option) else {
+                    bail!("Invalid option {} for {}", selected.option, choice.desc)
+                };
+
+                let mut picks = selected.picks.iter();
+                option.resolve(&mut picks, &mut items)?;
+
+                if picks.next().is_some() {
+                    bail!("Too many picks for {}", choice.desc)
+                }
+            }
+        }
+
+        Ok(items)
+    }
+}
+
+/// Returns the dice rolled and the multiplier of the starting wealth in gold pieces
+/// of a class taking gold instead of its starting equipment, e.g. (5, 10) for 5d4 x 10 gp
+/// # Arguments
+/// * `class_index` - Index from https://www.dnd5eapi.co/api/classes/
+pub fn starting_gold_dice(class_index: &str) -> Option<(u8, u32)> {
+    match class_index {
+        "barbarian" | "druid" => Some((2, 10)),
+        "bard" | "cleric" | "fighter" | "paladin" | "ranger" => Some((5, 10)),
+        "monk" => Some((5, 1)),
+        "rogue" | "warlock" | "wizard" => Some((4, 10)),
+        "sorcerer" => Some((3, 10)),
+        _ => None,
+    }
+}
+
+impl Character {
+    /// Rolls the starting wealth of the class, taken instead of its starting equipment,
+    /// and adds it to the purse
+    /// Returns the gold pieces added
+    /// # Arguments
+    /// * `class_index` - Index from https://www.dnd5eapi.co/api/classes/
+    /// * `rng` - The random generator used to roll, use a seeded one for reproducible results
+    pub fn take_starting_gold<R: Rng + ?Sized>(
+        &mut self,
+        class_index: &str,
+        rng: &mut R,
+    ) -> anyhow::Result<u32> {
+        let Some((dice, multiplier)) = starting_gold_dice(class_index) else {
+            bail!("No starting wealth for {}", class_index)
+        };
+
+        let gold = (0..dice).map(|_| rng.gen_range(1..=4u32)).sum::<u32>() * multiplier;
+        self.money.gp = self.money.gp.saturating_add(gold);
+
+        Ok(gold)
+    }
+}
@@ -0,0 +1,73 @@
+use dnd_character::abilities::{
+    ABILITY_SCORE_MAX, AbilityScore, AbilityScoreChange, AbilityScoreContribution,
+    AbilityScoreSource,
+};
+
+#[test]
+fn total_and_breakdown_of_sourced_modifiers() {
+    let mut strength = AbilityScore::new(15, false);
+    let race = AbilityScoreSource::Race("half-orc".to_string());
+    let improvement = AbilityScoreSource::AbilityScoreImprovement {
+        class: "fighter".to_string(),
+        level: 4,
+    };
+    let gauntlets = AbilityScoreSource::MagicItem("gauntlets-of-ogre-power".to_string());
+
+    strength.add_modifier(race.clone(), AbilityScoreChange::Bonus(2));
+    strength
+        .increase(1, ABILITY_SCORE_MAX, improvement.clone())
+        .unwrap();
+    assert_eq!(strength.total(), 18);
+    assert_eq!(strength.modifier(0), 4);
+
+    strength.add_modifier(gauntlets.clone(), AbilityScoreChange::Set(19));
+    assert_eq!(strength.total(), 19);
+
+    let breakdown = strength.breakdown();
+    assert_eq!(breakdown.base, 15);
+    assert_eq!(breakdown.total, 19);
+    assert_eq!(
+        breakdown.contributions,
+        vec![
+            AbilityScoreContribution {
+                source: race,
+                value: 2
+            },
+            AbilityScoreContribution {
+                source: improvement.clone(),
+                value: 1
+            },
+            AbilityScoreContribution {
+                source: gauntlets.clone(),
+                value: 1
+            },
+        ]
+    );
+
+    strength
+        .increase(1, ABILITY_SCORE_MAX, improvement)
+        .unwrap();
+    assert_eq!(strength.modifiers.len(), 3);
+    assert_eq!(strength.total(), 19);
+    assert_eq!(strength.breakdown().contributions[2].value, 0);
+
+    strength.remove_modifiers(&gauntlets);
+    assert_eq!(strength.total(), 19);
+    assert_eq!(strength.score, 15);
+}
+
+#[test]
+fn improvement_cap_ignores_set_scores() {
+    let mut strength = AbilityScore::new(19, false);
+    strength.add_modifier(
+        AbilityScoreSource::MagicItem("belt-of-giant-strength".to_string()),
+        AbilityScoreChange::Set(21),
+    );
+
+    let source = AbilityScoreSource::Feat("grappler".to_string());
+    strength
+        .increase(1, ABILITY_SCORE_MAX, source.clone())
+        .unwrap();
+    assert!(strength.increase(1, ABILITY_SCORE_MAX, source).is_err());
+    assert_eq!(strength.total(), 21);
+}
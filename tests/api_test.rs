@@ -1,7 +1,20 @@
 #![cfg(feature = "api")]
 
+use common::new_character;
 use dnd_character::Character;
-use dnd_character::api::classes::ChoosableCustomLevelFeatureOption;
+use dnd_character::abilities::{AbilityScoreContribution, AbilityScoreSource};
+use dnd_character::api::classes::{
+    ABILITY_SCORE_IMPROVEMENT_OPTIONS, ChoosableCustomLevelFeature,
+    ChoosableCustomLevelFeatureOption,
+};
+use dnd_character::api::spells::{
+    Spell, SpellPrerequisite, SpellPrerequisiteFeature, SpellPrerequisiteLevel,
+    SpellWithPrerequisite, SubclassSpells, get_filtered_spells, get_spell_details,
+};
+use dnd_character::classes::ClassSpellCasting;
+use dnd_character::spells::{AreaOfEffectShape, SpellFilter};
+
+mod common;
 
 #[tokio::test]
 async fn get_level_features() {
@@ -173,6 +186,445 @@ async fn choosable_custom_level_feature_option_serialization() {
     }
 }
 
+#[tokio::test]
+async fn subclass_option_serialization() {
+    let option = ChoosableCustomLevelFeatureOption::Subclass("berserker".to_string());
+
+    assert_eq!(option.as_index_str(), "berserker");
+    assert_eq!(serde_json::to_value(&option).unwrap(), "berserker");
+
+    // Unknown indexes are not mistaken for a subclass
+    assert!(ChoosableCustomLevelFeatureOption::from_index_str("berserker").is_none());
+    assert!(ChoosableCustomLevelFeatureOption::from_index_str("strenght-plus-one").is_none());
+
+    let feature = ChoosableCustomLevelFeature::Subclass(vec!["berserker".to_string()]);
+    assert_eq!(feature.option_from_index_str("berserker"), Some(option));
+    assert!(feature.option_from_index_str("champion").is_none());
+
+    assert!(matches!(
+        ChoosableCustomLevelFeatureOption::from_index_str("pact-of-the-blade"),
+        Some(ChoosableCustomLevelFeatureOption::PactOfTheBlade)
+    ));
+}
+
+#[tokio::test]
+async fn subclass_choice_validation() {
+    let mut dnd_character = new_character("barbarian");
+    let barbarian = dnd_character.classes.0.get_mut("barbarian").unwrap();
+    let feature = ChoosableCustomLevelFeature::Subclass(vec!["berserker".to_string()]);
+
+    assert!(
+        barbarian
+            .apply_option(ChoosableCustomLevelFeatureOption::Subclass(
+                "champion".to_string()
+            ))
+            .is_err()
+    );
+    assert!(
+        barbarian
+            .apply_choice(
+                &feature,
+                vec![ChoosableCustomLevelFeatureOption::Subclass(
+                    "champion".to_string()
+                )]
+            )
+            .await
+            .is_err()
+    );
+    assert!(barbarian.1.subclass.is_none());
+}
+
+#[tokio::test]
+async fn feat_instead_of_ability_score_improvement() {
+    let mut dnd_character = new_character("fighter");
+
+    let feature = ChoosableCustomLevelFeature::AbilityScoreImprovement {
+        level: 4,
+        abilities: ABILITY_SCORE_IMPROVEMENT_OPTIONS.to_vec(),
+        feats: vec!["grappler".to_string()],
+    };
+
+    let options = feature.to_options();
+    assert_eq!(options.len(), 2);
+    assert_eq!(options[0].len(), 6);
+    assert_eq!(options[1].len(), 6);
+
+    let option = feature.option_from_index_str("grappler").unwrap();
+    assert_eq!(
+        option,
+        ChoosableCustomLevelFeatureOption::Feat("grappler".to_string())
+    );
+    assert_eq!(
+        serde_json::to_value(&option).unwrap(),
+        option.as_index_str()
+    );
+    assert!(ChoosableCustomLevelFeatureOption::from_index_str("grappler").is_none());
+
+    let fighter = dnd_character.classes.0.get_mut("fighter").unwrap();
+    assert!(fighter.apply_option(option.clone()).is_err());
+    assert!(
+        fighter
+            .apply_choice(&feature, vec![option.clone()])
+            .await
+            .is_err()
+    );
+
+    // A feat replaces the whole improvement
+    assert!(
+        dnd_character
+            .apply_choice(
+                "fighter",
+                &feature,
+                vec![
+                    option.clone(),
+                    ChoosableCustomLevelFeatureOption::StrengthPlusOne
+                ]
+            )
+            .await
+            .is_err()
+    );
+    assert!(
+        dnd_character
+            .apply_choice(
+                "fighter",
+                &feature,
+                vec![ChoosableCustomLevelFeatureOption::Feat("tough".to_string())]
+            )
+            .await
+            .is_err()
+    );
+    assert!(!dnd_character.has_feat("grappler"));
+    assert!(
+        dnd_character
+            .abilities_score
+            .lock()
+            .unwrap()
+            .strength
+            .modifiers
+            .is_empty()
+    );
+
+    dnd_character
+        .apply_choice("fighter", &feature, vec![option.clone()])
+        .await
+        .unwrap();
+    assert!(dnd_character.has_feat("grappler"));
+    assert_eq!(dnd_character.feats, vec!["grappler".to_string()]);
+
+    // The feat can't be taken again, from the same class or another one
+    let wizard = new_character("wizard").classes.0.remove("wizard").unwrap();
+    dnd_character.classes.0.insert("wizard".to_string(), wizard);
+    assert!(
+        dnd_character
+            .apply_choice("fighter", &feature, vec![option.clone()])
+            .await
+            .is_err()
+    );
+    assert!(
+        dnd_character
+            .apply_choice("wizard", &feature, vec![option])
+            .await
+            .is_err()
+    );
+    assert_eq!(dnd_character.feats.len(), 1);
+}
+
+#[tokio::test]
+async fn ability_score_improvement_cap() {
+    let mut dnd_character = new_character("fighter");
+    dnd_character.abilities_score.lock().unwrap().strength.score = 19;
+
+    let fighter = dnd_character.classes.0.get_mut("fighter").unwrap();
+    assert_eq!(fighter.improvable_abilities().len(), 6);
+
+    let feature = ChoosableCustomLevelFeature::AbilityScoreImprovement {
+        level: 4,
+        abilities: fighter.improvable_abilities(),
+        feats: vec![],
+    };
+
+    assert!(
+        fighter
+            .apply_option(ChoosableCustomLevelFeatureOption::StrengthPlusOne)
+            .is_err()
+    );
+    assert!(
+        fighter
+            .apply_choice(
+                &feature,
+                vec![ChoosableCustomLevelFeatureOption::StrengthPlusOne]
+            )
+            .await
+            .is_err()
+    );
+
+    // Nothing is applied if one of the improvements goes over the maximum
+    assert!(
+        fighter
+            .apply_choice(
+                &feature,
+                vec![
+                    ChoosableCustomLevelFeatureOption::StrengthPlusOne,
+                    ChoosableCustomLevelFeatureOption::StrengthPlusOne
+                ]
+            )
+            .await
+            .is_err()
+    );
+    assert_eq!(
+        dnd_character
+            .abilities_score
+            .lock()
+            .unwrap()
+            .strength
+            .total(),
+        19
+    );
+
+    let fighter = dnd_character.classes.0.get_mut("fighter").unwrap();
+    fighter
+        .apply_choice(
+            &feature,
+            vec![
+                ChoosableCustomLevelFeatureOption::StrengthPlusOne,
+                ChoosableCustomLevelFeatureOption::DexterityPlusOne,
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        dnd_character
+            .abilities_score
+            .lock()
+            .unwrap()
+            .strength
+            .total(),
+        20
+    );
+
+    let fighter = &dnd_character.classes.0["fighter"];
+    let improvable = fighter.improvable_abilities();
+    assert_eq!(improvable.len(), 5);
+    assert!(
+        !improvable
+            .iter()
+            .any(|option| matches!(option, ChoosableCustomLevelFeatureOption::StrengthPlusOne))
+    );
+}
+
+#[tokio::test]
+async fn ability_score_improvement_sources() {
+    let mut dnd_character = new_character("fighter");
+    dnd_character.abilities_score.lock().unwrap().strength.score = 14;
+
+    // Both improvements are pending after going from level 3 to 8
+    let fighter = dnd_character.classes.0.get_mut("fighter").unwrap();
+    fighter.1.level = 8;
+    for level in [4, 8] {
+        let feature = ChoosableCustomLevelFeature::AbilityScoreImprovement {
+            level,
+            abilities: ABILITY_SCORE_IMPROVEMENT_OPTIONS.to_vec(),
+            feats: vec![],
+        };
+
+        fighter
+            .apply_choice(
+                &feature,
+                vec![
+                    ChoosableCustomLevelFeatureOption::StrengthPlusOne,
+                    ChoosableCustomLevelFeatureOption::StrengthPlusOne,
+                ],
+            )
+            .await
+            .unwrap();
+    }
+
+    let breakdown = dnd_character
+        .abilities_score
+        .lock()
+        .unwrap()
+        .strength
+        .breakdown();
+    assert_eq!(breakdown.total, 18);
+    assert_eq!(
+        breakdown.contributions,
+        [4, 8]
+            .map(|level| AbilityScoreContribution {
+                source: AbilityScoreSource::AbilityScoreImprovement {
+                    class: "fighter".to_string(),
+                    level,
+                },
+                value: 2,
+            })
+            .to_vec()
+    );
+}
+
+fn subclass_spell(
+    index: &str,
+    level: i32,
+    prerequisites: Vec<SpellPrerequisite>,
+) -> SpellWithPrerequisite {
+    SpellWithPrerequisite {
+        prerequisites: prerequisites.into_iter().map(Some).collect(),
+        spell: Spell {
+            index: index.to_string(),
+            level,
+            ritual: false,
+        },
+    }
+}
+
+fn land_spells() -> SubclassSpells {
+    let prerequisites = |level: i32, terrain: &str| {
+        vec![
+            SpellPrerequisite::Level(SpellPrerequisiteLevel { level }),
+            SpellPrerequisite::Feature(SpellPrerequisiteFeature {
+                index: terrain.to_string(),
+            }),
+        ]
+    };
+
+    SubclassSpells {
+        spells: Some(vec![
+            subclass_spell(
+                "hold-person",
+                2,
+                prerequisites(3, "circle-of-the-land-arctic"),
+            ),
+            subclass_spell("slow", 3, prerequisites(5, "circle-of-the-land-arctic")),
+            subclass_spell(
+                "mirror-image",
+                2,
+                prerequisites(3, "circle-of-the-land-coast"),
+            ),
+            subclass_spell("bless", 1, vec![]),
+        ]),
+    }
+}
+
+#[test]
+fn subclass_spells_by_level_and_terrain() {
+    assert_eq!(
+        land_spells().available_spells(3, Some("circle-of-the-land-arctic")),
+        vec![
+            vec![],
+            vec!["bless".to_string()],
+            vec!["hold-person".to_string()]
+        ]
+    );
+    assert_eq!(
+        land_spells().available_spells(5, Some("circle-of-the-land-coast")),
+        vec![
+            vec![],
+            vec!["bless".to_string()],
+            vec!["mirror-image".to_string()]
+        ]
+    );
+    assert_eq!(
+        land_spells().available_spells(5, Some("circle-of-the-land-arctic"))[3],
+        vec!["slow".to_string()]
+    );
+    assert_eq!(
+        land_spells().available_spells(2, None),
+        vec![vec![], vec!["bless".to_string()]]
+    );
+}
+
+#[tokio::test]
+async fn subclass_spells_are_ready() {
+    let mut dnd_character = new_character("cleric");
+    let cleric = dnd_character.classes.0.get_mut("cleric").unwrap();
+
+    if let Some(ClassSpellCasting::AlreadyKnowPrepared {
+        spells_prepared_index,
+        ..
+    }) = &mut cleric.1.spell_casting
+    {
+        *spells_prepared_index = vec![vec![], vec!["bless".to_string()]];
+    }
+    cleric.1.subclass_spells_index = vec![
+        vec![],
+        vec!["bless".to_string(), "cure-wounds".to_string()],
+        vec![],
+        vec!["spirit-guardians".to_string()],
+    ];
+
+    assert_eq!(
+        cleric.get_ready_spells().await.unwrap(),
+        vec![
+            vec![],
+            vec!["bless".to_string(), "cure-wounds".to_string()],
+            vec![],
+            vec!["spirit-guardians".to_string()],
+        ]
+    );
+}
+
+#[tokio::test]
+async fn land_terrain_adds_circle_spells() {
+    let mut dnd_character = new_character("druid");
+    let druid = dnd_character.classes.0.get_mut("druid").unwrap();
+    druid.1.level = 3;
+    druid.1.subclass = Some("land".to_string());
+
+    druid
+        .apply_choice(
+            &ChoosableCustomLevelFeature::CircleOfTheLandTerrain,
+            vec![ChoosableCustomLevelFeatureOption::CircleOfTheLandForest],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        druid.1.druid_land_terrain.as_deref(),
+        Some("circle-of-the-land-forest")
+    );
+    assert!(druid.1.subclass_spells_index[2].contains(&"barkskin".to_string()));
+}
+
+#[tokio::test]
+async fn race_ability_bonuses() {
+    let mut dnd_character = new_character("fighter");
+
+    dnd_character
+        .set_race("dwarf".to_string(), "hill-dwarf".to_string())
+        .await
+        .unwrap();
+    {
+        let abilities = dnd_character.abilities_score.lock().unwrap();
+        assert_eq!(
+            abilities.constitution.breakdown().contributions,
+            vec![AbilityScoreContribution {
+                source: AbilityScoreSource::Race("dwarf".to_string()),
+                value: 2,
+            }]
+        );
+        assert_eq!(
+            abilities.wisdom.breakdown().contributions,
+            vec![AbilityScoreContribution {
+                source: AbilityScoreSource::Race("hill-dwarf".to_string()),
+                value: 1,
+            }]
+        );
+    }
+
+    // The bonuses of the previous race are replaced
+    dnd_character
+        .set_race("human".to_string(), "".to_string())
+        .await
+        .unwrap();
+    let abilities = dnd_character.abilities_score.lock().unwrap();
+    assert_eq!(
+        abilities.constitution.breakdown().contributions,
+        vec![AbilityScoreContribution {
+            source: AbilityScoreSource::Race("human".to_string()),
+            value: 1,
+        }]
+    );
+    assert_eq!(abilities.wisdom.modifiers.len(), 1);
+}
+
 #[tokio::test]
 async fn wizard_spells() {
     let dnd_character = Character::new(
@@ -200,3 +652,32 @@ async fn wizard_spells() {
 
     assert_eq!(spells.len(), 204)
 }
+
+#[tokio::test]
+async fn spell_details_and_filter() {
+    let fireball = get_spell_details("fireball")
+        .await
+        .expect("Error in API Request");
+
+    assert_eq!(fireball.level, 3);
+    assert_eq!(fireball.school, "evocation");
+    assert_eq!(fireball.damage_type.as_deref(), Some("fire"));
+    assert_eq!(fireball.damage_at_slot(5), Some("10d6"));
+    assert_eq!(
+        fireball.dc.as_ref().map(|dc| dc.ability.as_str()),
+        Some("dex")
+    );
+
+    let filter = SpellFilter {
+        class: Some("wizard".to_string()),
+        levels: vec![3],
+        area_of_effect: Some(AreaOfEffectShape::Sphere),
+        ..Default::default()
+    };
+    let spells = get_filtered_spells(&filter)
+        .await
+        .expect("Error in API Request");
+
+    assert!(spells.iter().any(|spell| spell.index == "fireball"));
+    assert!(spells.iter().all(|spell| filter.matches(spell)));
+}
@@ -0,0 +1,96 @@
+use common::{armor, wear};
+use dnd_character::Character;
+use dnd_character::armor::{Armor, ArmorCategory};
+use dnd_character::equipment::ItemState;
+
+mod common;
+
+fn new_character(class: &str) -> Character {
+    let character = common::new_character(class);
+
+    {
+        let mut abilities = character.abilities_score.lock().unwrap();
+        abilities.strength.score = 10;
+        abilities.dexterity.score = 16;
+        abilities.constitution.score = 14;
+        abilities.wisdom.score = 10;
+    }
+
+    character
+}
+
+#[test]
+fn armor_and_shield() {
+    let mut character = new_character("fighter");
+    assert_eq!(character.class_armor(), 13);
+
+    wear(
+        &mut character,
+        armor("scale-mail", ArmorCategory::Medium, 14, Some(2)),
+    );
+    assert_eq!(character.class_armor(), 16);
+
+    wear(
+        &mut character,
+        Armor {
+            magic_bonus: 1,
+            ..armor("shield", ArmorCategory::Shield, 2, None)
+        },
+    );
+    assert_eq!(character.class_armor(), 19);
+
+    wear(
+        &mut character,
+        armor("plate-armor", ArmorCategory::Heavy, 18, None),
+    );
+    assert_eq!(character.class_armor(), 21);
+    assert!(character.stealth_disadvantage());
+    assert!(
+        !character
+            .worn_armor()
+            .unwrap()
+            .strength_requirement_met(&character.abilities_score.lock().unwrap())
+    );
+
+    character
+        .classes
+        .0
+        .get_mut("fighter")
+        .unwrap()
+        .1
+        .fighting_style = Some("fighter-fighting-style-defense".to_string());
+    assert_eq!(character.class_armor(), 22);
+
+    character
+        .set_item_state("plate-armor", ItemState::Carried)
+        .unwrap();
+    assert_eq!(character.class_armor(), 16);
+}
+
+#[test]
+fn unarmored_defense() {
+    let mut character = new_character("barbarian");
+    assert_eq!(character.class_armor(), 15);
+
+    wear(
+        &mut character,
+        armor("shield", ArmorCategory::Shield, 2, None),
+    );
+    assert_eq!(character.class_armor(), 17);
+
+    let mut character = new_character("monk");
+    character.abilities_score.lock().unwrap().wisdom.score = 16;
+    assert_eq!(character.class_armor(), 16);
+
+    wear(
+        &mut character,
+        armor("shield", ArmorCategory::Shield, 2, None),
+    );
+    assert_eq!(character.class_armor(), 15);
+
+    let mut character = new_character("sorcerer");
+    assert_eq!(character.class_armor(), 13);
+
+    character.classes.0.get_mut("sorcerer").unwrap().1.subclass = Some("draconic".to_string());
+    assert_eq!(character.class_armor(), 16);
+}
@@ -0,0 +1,138 @@
+use common::{equip, weapon};
+use dnd_character::Character;
+use dnd_character::attacks::AttackMode;
+use dnd_character::equipment::WeaponRange;
+
+mod common;
+
+fn new_character(class: &str, fighting_style: Option<&str>) -> Character {
+    let mut character = common::new_character(class);
+
+    {
+        let mut abilities = character.abilities_score.lock().unwrap();
+        abilities.strength.score = 16;
+        abilities.dexterity.score = 14;
+    }
+
+    if let Some(class) = character.classes.0.get_mut(class) {
+        class.1.fighting_style = fighting_style.map(str::to_string);
+    }
+
+    character
+}
+
+#[test]
+fn versatile_weapon_with_dueling() {
+    let mut character = new_character("fighter", Some("fighter-fighting-style-dueling"));
+    equip(
+        &mut character,
+        weapon(
+            "longsword",
+            "martial-weapons",
+            WeaponRange::Melee,
+            "1d8",
+            &["versatile"],
+        ),
+    );
+
+    let attacks = character.attacks();
+    assert_eq!(attacks.len(), 2);
+
+    let one_handed = &attacks[0];
+    assert_eq!(one_handed.mode, AttackMode::Wielded);
+    assert_eq!(one_handed.ability, "str");
+    assert_eq!(one_handed.attack_bonus, 5);
+    assert_eq!(one_handed.damage(), "1d8+5");
+
+    let two_handed = &attacks[1];
+    assert_eq!(two_handed.mode, AttackMode::TwoHanded);
+    assert_eq!(two_handed.damage(), "1d10+3");
+}
+
+#[test]
+fn great_weapon_fighting_and_archery() {
+    let mut character = new_character("fighter", Some("fighting-style-great-weapon-fighting"));
+    equip(
+        &mut character,
+        weapon(
+            "greatsword",
+            "martial-weapons",
+            WeaponRange::Melee,
+            "2d6",
+            &["heavy", "two-handed"],
+        ),
+    );
+    assert!(character.attacks()[0].reroll_low_damage);
+
+    let mut character = new_character("fighter", Some("fighter-fighting-style-archery"));
+    equip(
+        &mut character,
+        weapon(
+            "longbow",
+            "martial-weapons",
+            WeaponRange::Ranged,
+            "1d8",
+            &["ammunition", "heavy", "two-handed"],
+        ),
+    );
+
+    let attack = &character.attacks()[0];
+    assert_eq!(attack.ability, "dex");
+    assert_eq!(attack.attack_bonus, 6);
+    assert!(!attack.reroll_low_damage);
+    assert_eq!(attack.long_range, Some(600));
+}
+
+#[test]
+fn two_weapon_fighting() {
+    let dagger = weapon(
+        "dagger",
+        "simple-weapons",
+        WeaponRange::Melee,
+        "1d4",
+        &["finesse", "light", "thrown"],
+    );
+    let shortsword = weapon(
+        "shortsword",
+        "martial-weapons",
+        WeaponRange::Melee,
+        "1d6",
+        &["finesse", "light"],
+    );
+
+    let mut character = new_character("wizard", None);
+    equip(&mut character, dagger.clone());
+    equip(&mut character, shortsword.clone());
+
+    let attacks = character.attacks();
+    let off_hand = attacks
+        .iter()
+        .find(|attack| attack.index == "dagger" && attack.mode == AttackMode::OffHand)
+        .unwrap();
+    assert_eq!(off_hand.attack_bonus, 5);
+    assert_eq!(off_hand.damage(), "1d4");
+
+    let thrown = attacks
+        .iter()
+        .find(|attack| attack.mode == AttackMode::Thrown)
+        .unwrap();
+    assert_eq!(thrown.normal_range, 20);
+
+    // Wizards aren't proficient with shortswords
+    let shortsword_attack = attacks
+        .iter()
+        .find(|attack| attack.index == "shortsword" && attack.mode == AttackMode::Wielded)
+        .unwrap();
+    assert_eq!(shortsword_attack.attack_bonus, 3);
+
+    let mut character = new_character("ranger", Some("ranger-fighting-style-two-weapon-fighting"));
+    equip(&mut character, dagger);
+    equip(&mut character, shortsword);
+    assert!(
+        character
+            .attacks()
+            .iter()
+            .filter(|attack| attack.mode == AttackMode::OffHand)
+            .all(|attack| attack.damage_bonus == 3)
+    );
+}
@@ -0,0 +1,88 @@
+use dnd_character::Character;
+
+mod common;
+
+fn new_character(class: &str) -> Character {
+    let character = common::new_character(class);
+    {
+        let mut abilities = character.abilities_score.lock().unwrap();
+        abilities.dexterity.score = 14;
+        abilities.wisdom.score = 12;
+        abilities.intelligence.score = 8;
+    }
+    character
+}
+
+#[test]
+fn initiative_and_passive_scores() {
+    let mut character = new_character("bard");
+    assert_eq!(character.initiative(), 2);
+    assert_eq!(character.passive_perception(), 11);
+    assert_eq!(character.passive_investigation(), 9);
+    assert_eq!(character.passive_insight(), 11);
+    assert!(character.passive_score("luck").is_err());
+
+    // Jack of All Trades from the 2nd bard level
+    character.classes.0.get_mut("bard").unwrap().1.level = 2;
+    assert_eq!(character.jack_of_all_trades_bonus(), 1);
+    assert_eq!(character.initiative(), 3);
+    assert_eq!(character.passive_perception(), 12);
+
+    character.add_exhaustion(1);
+    assert_eq!(character.passive_perception(), 7);
+}
+
+#[test]
+fn remarkable_athlete() {
+    let mut character = new_character("fighter");
+    let fighter = &mut character.classes.0.get_mut("fighter").unwrap().1;
+    fighter.level = 7;
+    fighter.subclass = Some("champion".to_string());
+
+    assert_eq!(character.remarkable_athlete_bonus(), 1);
+    assert_eq!(character.initiative(), 3);
+    // Only for strength, dexterity and constitution checks
+    assert_eq!(character.passive_perception(), 11);
+}
+
+#[test]
+fn speed_bonus() {
+    let mut character = new_character("monk");
+    assert_eq!(character.effective_speed(30), 30);
+
+    character
+        .classes
+        .0
+        .get_mut("monk")
+        .unwrap()
+        .1
+        .monk_unarmored_movement = Some(10);
+    assert_eq!(character.speed_bonus(), 10);
+    assert_eq!(character.effective_speed(30), 40);
+
+    let mut character = new_character("barbarian");
+    character.classes.0.get_mut("barbarian").unwrap().1.level = 5;
+    assert_eq!(character.effective_speed(30), 40);
+
+    character.add_exhaustion(2);
+    assert_eq!(character.effective_speed(30), 20);
+}
+
+#[test]
+fn attacks_per_action() {
+    let mut character = new_character("fighter");
+    assert_eq!(character.attacks_per_action(), 1);
+
+    character
+        .classes
+        .0
+        .get_mut("fighter")
+        .unwrap()
+        .1
+        .fighter_extra_attacks = Some(2);
+    assert_eq!(character.attacks_per_action(), 3);
+
+    let mut character = new_character("paladin");
+    character.classes.0.get_mut("paladin").unwrap().1.level = 5;
+    assert_eq!(character.attacks_per_action(), 2);
+}
@@ -0,0 +1,151 @@
+//! Fixtures shared by the integration tests, each test file only uses some of them
+#![allow(dead_code)]
+
+use dnd_character::Character;
+use dnd_character::armor::{Armor, ArmorCategory};
+use dnd_character::equipment::{Item, ItemDetails, ItemState, Weapon, WeaponRange};
+use dnd_character::magic_items::{Charges, MagicItem, MagicItemEffect, Rarity};
+use dnd_character::spells::SpellDetails;
+use std::collections::BTreeMap;
+
+/// Level 0 human of the class, the ability scores are left to the test
+pub fn new_character(class: &str) -> Character {
+    new_character_of_race(class, "human", "human")
+}
+
+pub fn new_character_of_race(class: &str, race: &str, subrace: &str) -> Character {
+    Character::new(
+        class.to_string(),
+        "a".to_string(),
+        16,
+        race.to_string(),
+        subrace.to_string(),
+        "chaotic-neutral".to_string(),
+        "".to_string(),
+        "".to_string(),
+        "".to_string(),
+    )
+}
+
+pub fn item(index: &str, weight: f32, quantity: u16) -> Item {
+    Item {
+        weight,
+        ..Item::custom(index.to_string(), quantity)
+    }
+}
+
+/// Inserts the item and equips it
+pub fn equip(character: &mut Character, item: Item) {
+    let index = item.index.clone();
+    character.insert_item(item);
+    character
+        .set_item_state(&index, ItemState::Equipped)
+        .unwrap();
+}
+
+pub fn armor(index: &str, category: ArmorCategory, base: u8, max_dex_bonus: Option<u8>) -> Armor {
+    Armor {
+        index: index.to_string(),
+        category,
+        base,
+        dex_bonus: category != ArmorCategory::Heavy && category != ArmorCategory::Shield,
+        max_dex_bonus,
+        str_minimum: if category == ArmorCategory::Heavy {
+            15
+        } else {
+            0
+        },
+        stealth_disadvantage: category == ArmorCategory::Heavy,
+        magic_bonus: 0,
+    }
+}
+
+/// Inserts the armor as an item and equips it
+pub fn wear(character: &mut Character, armor: Armor) {
+    equip(
+        character,
+        Item {
+            details: ItemDetails::Armor(armor.clone()),
+            ..item(&armor.index, 0.0, 1)
+        },
+    );
+}
+
+pub fn chain_mail() -> Item {
+    Item {
+        category: "armor".to_string(),
+        details: ItemDetails::Armor(Armor {
+            str_minimum: 13,
+            ..armor("chain-mail", ArmorCategory::Heavy, 16, None)
+        }),
+        ..item("chain-mail", 55.0, 1)
+    }
+}
+
+pub fn weapon(
+    index: &str,
+    category: &str,
+    range: WeaponRange,
+    damage_dice: &str,
+    properties: &[&str],
+) -> Item {
+    Item {
+        details: ItemDetails::Weapon(Weapon {
+            category: category.to_string(),
+            range,
+            damage_dice: Some(damage_dice.to_string()),
+            damage_type: Some("slashing".to_string()),
+            two_handed_damage_dice: properties
+                .contains(&"versatile")
+                .then(|| "1d10".to_string()),
+            normal_range: if range == WeaponRange::Melee { 5 } else { 150 },
+            long_range: (range == WeaponRange::Ranged).then_some(600),
+            throw_normal_range: properties.contains(&"thrown").then_some(20),
+            throw_long_range: properties.contains(&"thrown").then_some(60),
+            properties: properties.iter().map(|p| p.to_string()).collect(),
+        }),
+        ..item(index, 0.0, 1)
+    }
+}
+
+/// Rare item requiring attunement
+pub fn magic_item(index: &str, effects: Vec<MagicItemEffect>, charges: Option<Charges>) -> Item {
+    Item {
+        magic: Some(MagicItem {
+            rarity: Rarity::Rare,
+            requires_attunement: true,
+            attuned: false,
+            effects,
+            charges,
+        }),
+        ..item(index, 0.0, 1)
+    }
+}
+
+/// Instantaneous spell without damage, the tests override the fields they check
+pub fn spell(index: &str, level: u8) -> SpellDetails {
+    SpellDetails {
+        index: index.to_string(),
+        name: index.to_string(),
+        level,
+        school: "abjuration".to_string(),
+        casting_time: "1 action".to_string(),
+        range: "Self".to_string(),
+        components: vec![],
+        material: None,
+        duration: "Instantaneous".to_string(),
+        concentration: false,
+        ritual: false,
+        attack_type: None,
+        damage_type: None,
+        damage_at_slot_level: BTreeMap::new(),
+        damage_at_character_level: BTreeMap::new(),
+        heal_at_slot_level: BTreeMap::new(),
+        dc: None,
+        area_of_effect: None,
+        classes: vec![],
+        subclasses: vec![],
+        desc: vec![],
+        higher_level: vec![],
+    }
+}
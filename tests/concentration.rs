@@ -0,0 +1,88 @@
+use dnd_character::Character;
+use dnd_character::concentration::{ConcentrationCheck, concentration_save_dc};
+use dnd_character::hit_points::HitPointEvent;
+use dnd_character::spells::SpellDetails;
+
+mod common;
+
+fn new_character() -> Character {
+    let mut character = common::new_character("wizard");
+    character.abilities_score.lock().unwrap().constitution.score = 14;
+    character.hit_dice_result = 18;
+    character.hp = character.max_hp();
+    character
+}
+
+fn spell(index: &str, duration: &str, concentration: bool) -> SpellDetails {
+    SpellDetails {
+        duration: duration.to_string(),
+        concentration,
+        ..common::spell(index, 1)
+    }
+}
+
+#[test]
+fn save_dc() {
+    assert_eq!(concentration_save_dc(5), 10);
+    assert_eq!(concentration_save_dc(21), 10);
+    assert_eq!(concentration_save_dc(22), 11);
+}
+
+#[test]
+fn single_concentration() {
+    let mut character = new_character();
+
+    assert_eq!(
+        character.start_spell_effect(&spell("magic-missile", "Instantaneous", false), 1),
+        None
+    );
+    assert!(character.active_effects.is_empty());
+
+    character.start_spell_effect(&spell("mage-armor", "8 hours", false), 1);
+    character.start_spell_effect(&spell("bless", "Up to 1 minute", true), 1);
+    assert_eq!(character.concentration().unwrap().spell, "bless");
+
+    let dropped = character.start_spell_effect(&spell("haste", "Up to 1 minute", true), 3);
+    assert_eq!(dropped.unwrap().spell, "bless");
+    assert_eq!(character.concentration().unwrap().spell, "haste");
+    assert_eq!(character.active_effects.len(), 2);
+
+    assert!(character.end_spell_effect("bless").is_err());
+    assert!(character.end_spell_effect("mage-armor").is_ok());
+    assert_eq!(character.active_effects.len(), 1);
+}
+
+#[test]
+fn damage_and_saves() {
+    let mut character = new_character();
+
+    character.take_damage(3, "fire");
+    assert_eq!(character.hp, 17);
+
+    character.start_spell_effect(&spell("bless", "Up to 1 minute", true), 1);
+    let events = character.take_damage(4, "fire");
+    let check = ConcentrationCheck {
+        spell: "bless".to_string(),
+        dc: 10,
+    };
+    assert_eq!(
+        events.last(),
+        Some(&HitPointEvent::ConcentrationCheck(check.clone()))
+    );
+
+    // d20 + 2 from constitution
+    assert!(character.resolve_concentration_check(&check, 8).unwrap());
+    assert!(character.concentration().is_some());
+    assert!(!character.resolve_concentration_check(&check, 7).unwrap());
+    assert!(character.concentration().is_none());
+    assert!(character.resolve_concentration_check(&check, 20).is_err());
+
+    character.start_spell_effect(&spell("bless", "Up to 1 minute", true), 1);
+    let events = character.take_damage(20, "fire");
+    assert_eq!(character.hp, 0);
+    assert_eq!(
+        events.last(),
+        Some(&HitPointEvent::ConcentrationLost("bless".to_string()))
+    );
+    assert!(character.concentration().is_none());
+}
@@ -0,0 +1,119 @@
+use dnd_character::Character;
+use dnd_character::classes::RestType;
+use dnd_character::conditions::{Condition, ConditionDuration};
+
+mod common;
+
+fn new_character() -> Character {
+    let mut character = common::new_character("fighter");
+    character.abilities_score.lock().unwrap().constitution.score = 10;
+    character.hit_dice_result = 20;
+    character.hp = character.max_hp();
+    character
+}
+
+fn condition(index: &str, source: Option<&str>, duration: ConditionDuration) -> Condition {
+    Condition {
+        index: index.to_string(),
+        source: source.map(str::to_string),
+        duration,
+    }
+}
+
+#[test]
+fn conditions_with_durations() {
+    let mut character = new_character();
+
+    character
+        .add_condition(condition(
+            "poisoned",
+            Some("giant-spider"),
+            ConditionDuration::UntilRest(RestType::Short),
+        ))
+        .unwrap();
+    character
+        .add_condition(condition("stunned", None, ConditionDuration::Rounds(2)))
+        .unwrap();
+    assert!(
+        character
+            .add_condition(condition("exhaustion", None, ConditionDuration::Indefinite))
+            .is_err()
+    );
+
+    assert!(character.has_condition("incapacitated"));
+    assert!(character.disadvantage_on_attacks());
+    assert!(character.disadvantage_on_ability_checks());
+    assert_eq!(character.effective_speed(30), 0);
+
+    assert!(character.end_round().is_empty());
+    assert_eq!(character.end_round()[0].index, "stunned");
+    assert!(!character.has_condition("incapacitated"));
+    assert_eq!(character.effective_speed(30), 30);
+
+    assert!(
+        character
+            .remove_condition("poisoned", Some("snake"))
+            .is_err()
+    );
+    character.short_rest();
+    assert!(!character.has_condition("poisoned"));
+}
+
+#[test]
+fn unconscious_at_zero_hit_points() {
+    let mut character = new_character();
+
+    character.take_damage(20, "slashing");
+    assert!(character.has_condition("unconscious"));
+    assert!(character.has_condition("incapacitated"));
+
+    character.heal(1).unwrap();
+    assert!(!character.has_condition("unconscious"));
+}
+
+#[test]
+fn exhaustion_penalties() {
+    let mut character = new_character();
+    let max_hp = character.max_hp();
+
+    character.add_exhaustion(1);
+    assert!(character.has_condition("exhaustion"));
+    assert!(character.disadvantage_on_ability_checks());
+    assert!(!character.disadvantage_on_attacks());
+
+    character.add_exhaustion(1);
+    assert_eq!(character.effective_speed(30), 15);
+
+    character.add_exhaustion(1);
+    assert!(character.disadvantage_on_attacks());
+    assert!(character.disadvantage_on_saving_throw("wis"));
+
+    character.add_exhaustion(1);
+    assert_eq!(character.max_hp(), max_hp / 2);
+    assert_eq!(character.hp, max_hp / 2);
+
+    character.add_exhaustion(1);
+    assert_eq!(character.effective_speed(30), 0);
+
+    character.remove_exhaustion(4);
+    assert_eq!(character.exhaustion, 1);
+    assert_eq!(character.max_hp(), max_hp);
+
+    character.add_exhaustion(10);
+    assert_eq!(character.exhaustion, 6);
+    assert!(character.is_dead());
+}
+
+#[tokio::test]
+async fn dead_from_exhaustion_after_new_day() {
+    let mut character = new_character();
+
+    character.take_damage(5, "slashing");
+    character.add_exhaustion(6);
+    let hp = character.hp;
+    character.new_day().await.unwrap();
+
+    assert_eq!(character.exhaustion, 6);
+    assert_eq!(character.hp, hp);
+    assert!(character.is_dead());
+}
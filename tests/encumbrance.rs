@@ -0,0 +1,59 @@
+use common::{chain_mail, item};
+use dnd_character::Character;
+use dnd_character::encumbrance::Encumbrance;
+use dnd_character::equipment::{Item, ItemState};
+
+mod common;
+
+fn new_character() -> Character {
+    let character = common::new_character("fighter");
+    character.abilities_score.lock().unwrap().strength.score = 10;
+
+    character
+}
+
+#[test]
+fn encumbrance_thresholds() {
+    let mut character = new_character();
+    assert_eq!(character.carrying_capacity(), 150);
+    assert_eq!(character.push_drag_lift(), 300);
+
+    character.insert_item(item("rope-hempen-50-feet", 10.0, 5));
+    assert_eq!(character.carried_weight(), 50.0);
+    assert_eq!(character.encumbrance(), Encumbrance::Unencumbered);
+    assert_eq!(character.effective_speed(30), 30);
+
+    character.add_item("rope-hempen-50-feet", 1);
+    assert_eq!(character.encumbrance(), Encumbrance::Encumbered);
+    assert_eq!(character.effective_speed(30), 20);
+
+    character.add_item("rope-hempen-50-feet", 5);
+    assert_eq!(character.encumbrance(), Encumbrance::HeavilyEncumbered);
+    assert_eq!(character.effective_speed(30), 10);
+
+    character.add_item("rope-hempen-50-feet", 5);
+    assert_eq!(character.encumbrance(), Encumbrance::OverCapacity);
+    assert_eq!(character.effective_speed(30), 5);
+
+    character.insert_item(Item {
+        state: ItemState::Stowed,
+        ..item("anvil", 1000.0, 1)
+    });
+    assert_eq!(character.carried_weight(), 160.0);
+}
+
+#[test]
+fn heavy_armor_strength_requirement() {
+    let mut character = new_character();
+    character.insert_item(chain_mail());
+    assert_eq!(character.encumbrance(), Encumbrance::Encumbered);
+    assert_eq!(character.effective_speed(30), 20);
+
+    character
+        .set_item_state("chain-mail", ItemState::Equipped)
+        .unwrap();
+    assert_eq!(character.effective_speed(30), 10);
+
+    character.abilities_score.lock().unwrap().strength.score = 13;
+    assert_eq!(character.effective_speed(30), 30);
+}
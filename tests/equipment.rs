@@ -0,0 +1,59 @@
+use common::{chain_mail, new_character};
+use dnd_character::Character;
+use dnd_character::equipment::{Item, ItemState};
+
+mod common;
+
+#[test]
+fn stack_equip_and_remove_items() {
+    let mut character = new_character("fighter");
+
+    character.insert_item(Item::custom("torch".to_string(), 3));
+    character.add_item("torch", 2);
+    assert_eq!(character.inventory.len(), 1);
+    assert_eq!(character.inventory[0].quantity, 5);
+
+    character.remove_item("torch", Some(2)).unwrap();
+    assert_eq!(character.inventory[0].quantity, 3);
+    character.remove_item("torch", None).unwrap();
+    assert!(character.remove_item("torch", None).is_err());
+    assert!(character.inventory.is_empty());
+
+    character.insert_item(chain_mail());
+    character.insert_item(chain_mail());
+    character
+        .set_item_state("chain-mail", ItemState::Equipped)
+        .unwrap();
+    assert_eq!(character.inventory.len(), 2);
+    assert_eq!(character.worn_armor().unwrap().base, 16);
+
+    character
+        .set_item_state("chain-mail", ItemState::Equipped)
+        .unwrap();
+    assert_eq!(character.equipped_items().count(), 1);
+    assert_eq!(
+        character
+            .inventory
+            .iter()
+            .find(|item| item.state == ItemState::Carried)
+            .unwrap()
+            .quantity,
+        1
+    );
+}
+
+#[test]
+fn legacy_inventory_migration() {
+    let json = include_str!("schema_0_13_17.json")
+        .replace("\"inventory\":{}", "\"inventory\":{\"rope\":2}");
+
+    let character: Character = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(character.inventory.len(), 1);
+    assert_eq!(character.inventory[0].index, "rope");
+    assert_eq!(character.inventory[0].quantity, 2);
+
+    let json = serde_json::to_string(&character).unwrap();
+    let character: Character = serde_json::from_str(&json).unwrap();
+    assert_eq!(character.inventory.len(), 1);
+}
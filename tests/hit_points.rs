@@ -0,0 +1,189 @@
+use dnd_character::Character;
+use dnd_character::hit_points::{HitPointEvent, HitPointsChoice};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+mod common;
+
+fn new_character(class: &str, subrace: &str) -> Character {
+    common::new_character_of_race(class, "dwarf", subrace)
+}
+
+#[test]
+fn first_level_takes_max_hit_die() {
+    let mut character = new_character("barbarian", "mountain-dwarf");
+    character.abilities_score.lock().unwrap().constitution.score = 14;
+    character
+        .classes
+        .0
+        .get_mut("barbarian")
+        .unwrap()
+        .1
+        .pending_hit_points = 2;
+
+    let mut rng = StdRng::seed_from_u64(42);
+
+    let first = character
+        .choose_hit_points("barbarian", HitPointsChoice::Roll, &mut rng)
+        .unwrap();
+    assert_eq!(first, 12);
+    assert_eq!(character.hp, 14);
+
+    character.add_experience(300);
+
+    let second = character
+        .choose_hit_points("barbarian", HitPointsChoice::Average, &mut rng)
+        .unwrap();
+    assert_eq!(second, 7);
+    assert_eq!(character.max_hp(), 12 + 7 + 2 * 2);
+    assert_eq!(character.hp, character.max_hp());
+
+    assert!(
+        character
+            .choose_hit_points("barbarian", HitPointsChoice::Average, &mut rng)
+            .is_err()
+    );
+    assert!(character.pending_hit_points().is_empty());
+}
+
+#[test]
+fn rolled_hit_points_are_reproducible() {
+    let roll = |seed| {
+        let mut character = new_character("wizard", "mountain-dwarf");
+        character.hit_dice_result = 6;
+        character
+            .classes
+            .0
+            .get_mut("wizard")
+            .unwrap()
+            .1
+            .pending_hit_points = 1;
+
+        character
+            .choose_hit_points(
+                "wizard",
+                HitPointsChoice::Roll,
+                &mut StdRng::seed_from_u64(seed),
+            )
+            .unwrap()
+    };
+
+    assert_eq!(roll(7), roll(7));
+    assert!((1..=6).contains(&roll(7)));
+}
+
+#[test]
+fn dwarven_toughness_and_tough_apply_to_every_level() {
+    let mut character = new_character("fighter", "hill-dwarf");
+    character.abilities_score.lock().unwrap().constitution.score = 10;
+    character.hit_dice_result = 10 + 6;
+    character.add_experience(300);
+
+    assert_eq!(character.max_hp(), 16 + 2);
+
+    character.feats.push("tough".to_string());
+    assert_eq!(character.max_hp(), 16 + 2 * 3);
+}
+
+fn wounded_character() -> Character {
+    let mut character = new_character("fighter", "mountain-dwarf");
+    character.abilities_score.lock().unwrap().constitution.score = 10;
+    character.hit_dice_result = 10;
+    character.hp = character.max_hp();
+    character
+}
+
+#[test]
+fn temporary_hit_points_absorb_damage() {
+    let mut character = wounded_character();
+
+    character.set_temporary_hp(5);
+    // They don't stack, the highest amount is kept
+    character.set_temporary_hp(3);
+    assert_eq!(character.temporary_hp, 5);
+
+    let events = character.take_damage(7, "slashing");
+    assert_eq!(
+        events,
+        vec![
+            HitPointEvent::DamageTaken {
+                amount: 7,
+                damage_type: "slashing".to_string()
+            },
+            HitPointEvent::TemporaryHitPointsLost(5),
+            HitPointEvent::HitPointsLost(2),
+        ]
+    );
+    assert_eq!(character.temporary_hp, 0);
+    assert_eq!(character.hp, 8);
+
+    assert_eq!(
+        character.heal(5).unwrap(),
+        vec![HitPointEvent::HitPointsRegained(2)]
+    );
+    assert_eq!(character.hp, 10);
+}
+
+#[test]
+fn death_saving_throws() {
+    let mut character = wounded_character();
+
+    let events = character.take_damage(15, "piercing");
+    assert_eq!(events.last(), Some(&HitPointEvent::Unconscious));
+    assert!(character.is_dying());
+    assert!(character.stabilize().is_ok());
+    assert!(!character.is_dying());
+    assert!(character.death_saving_throw(12).is_err());
+
+    // Damage while unconscious is a failed death saving throw
+    character.take_damage(1, "piercing");
+    assert!(character.is_dying());
+    assert_eq!(character.death_saves.failures, 1);
+
+    assert!(character.death_saving_throw(0).is_err());
+    assert!(character.death_saving_throw(21).is_err());
+    assert_eq!(character.death_saves.failures, 1);
+    assert_eq!(character.death_saves.successes, 0);
+
+    character.death_saving_throw(10).unwrap();
+    character.death_saving_throw(15).unwrap();
+    assert_eq!(
+        character.death_saving_throw(11).unwrap().last(),
+        Some(&HitPointEvent::Stabilized)
+    );
+    assert!(character.is_unconscious());
+
+    assert_eq!(
+        character.heal(3).unwrap(),
+        vec![
+            HitPointEvent::Conscious,
+            HitPointEvent::HitPointsRegained(3)
+        ]
+    );
+    assert_eq!(character.death_saves.failures, 0);
+
+    character.take_damage(3, "piercing");
+    character.death_saving_throw(1).unwrap();
+    assert_eq!(
+        character.death_saving_throw(5).unwrap().last(),
+        Some(&HitPointEvent::Died)
+    );
+    assert!(character.is_dead());
+    assert!(character.heal(5).is_err());
+}
+
+#[test]
+fn massive_damage_kills() {
+    let mut character = wounded_character();
+
+    character.death_saving_throw(20).unwrap_err();
+    let events = character.take_damage(20, "bludgeoning");
+    assert_eq!(events.last(), Some(&HitPointEvent::Died));
+    assert!(character.is_dead());
+    assert!(character.take_damage(5, "bludgeoning").is_empty());
+
+    let mut character = wounded_character();
+    character.take_damage(10, "bludgeoning");
+    character.death_saving_throw(20).unwrap();
+    assert_eq!(character.hp, 1);
+}
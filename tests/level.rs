@@ -144,6 +144,13 @@ async fn test_character_progression_to_level_20() {
     // This can be customized based on the class and expected features
     let mut expected_choices: HashMap<u8, Vec<ChatPendingChoice>> = HashMap::new();
 
+    expected_choices.insert(
+        3,
+        vec![ChatPendingChoice {
+            index: "subclass".to_string(),
+            choices: vec![vec!["berserker".to_string()]],
+        }],
+    );
     expected_choices.insert(5, vec![ability_score_improvement()]);
     expected_choices.insert(9, vec![ability_score_improvement()]); // Adding level 9
     expected_choices.insert(13, vec![ability_score_improvement()]);
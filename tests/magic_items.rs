@@ -0,0 +1,130 @@
+use common::{magic_item, new_character};
+use dnd_character::equipment::{Item, ItemState};
+use dnd_character::magic_items::{Charges, MagicItemEffect, Recharge};
+
+mod common;
+
+#[test]
+fn attunement_activates_effects() {
+    let mut character = new_character("fighter");
+    let armor_class = character.class_armor();
+    let saving_throw = character.saving_throw("dex").unwrap();
+
+    character.insert_item(magic_item(
+        "ring-of-protection",
+        vec![
+            MagicItemEffect::ArmorClassBonus(1),
+            MagicItemEffect::SavingThrowBonus(1),
+        ],
+        None,
+    ));
+    character
+        .set_item_state("ring-of-protection", ItemState::Equipped)
+        .unwrap();
+    assert_eq!(character.class_armor(), armor_class);
+
+    character.attune("ring-of-protection").unwrap();
+    assert_eq!(character.class_armor(), armor_class + 1);
+    assert_eq!(character.saving_throw("dex").unwrap(), saving_throw + 1);
+
+    character.end_attunement("ring-of-protection").unwrap();
+    assert_eq!(character.class_armor(), armor_class);
+}
+
+#[test]
+fn attunement_limit() {
+    let mut character = new_character("fighter");
+
+    for index in ["a", "b", "c", "d"] {
+        character.insert_item(magic_item(index, vec![], None));
+    }
+
+    for index in ["a", "b", "c"] {
+        character.attune(index).unwrap();
+    }
+    assert!(character.attune("d").is_err());
+    assert_eq!(character.attuned_items().count(), 3);
+
+    character.end_attunement("a").unwrap();
+    character.attune("d").unwrap();
+
+    // The new units of an attuned item aren't attuned
+    character.add_item("d", 5);
+    assert_eq!(
+        character
+            .attuned_items()
+            .map(|item| item.quantity)
+            .sum::<u16>(),
+        3
+    );
+    assert!(
+        character
+            .inventory
+            .iter()
+            .any(|item| item.index == "d" && item.quantity == 5 && item.magic.is_some())
+    );
+
+    character.insert_item(Item::custom("rope".to_string(), 1));
+    assert!(character.attune("rope").is_err());
+}
+
+#[test]
+fn set_ability_score() {
+    let mut character = new_character("fighter");
+    character.abilities_score.lock().unwrap().strength.score = 10;
+
+    character.insert_item(magic_item(
+        "gauntlets-of-ogre-power",
+        vec![MagicItemEffect::SetAbilityScore {
+            ability: "str".to_string(),
+            score: 19,
+        }],
+        None,
+    ));
+    character.attune("gauntlets-of-ogre-power").unwrap();
+    assert_eq!(
+        character.abilities_score.lock().unwrap().strength.total(),
+        10
+    );
+
+    character
+        .set_item_state("gauntlets-of-ogre-power", ItemState::Equipped)
+        .unwrap();
+    assert_eq!(
+        character.abilities_score.lock().unwrap().strength.total(),
+        19
+    );
+
+    character
+        .remove_item("gauntlets-of-ogre-power", None)
+        .unwrap();
+    assert_eq!(
+        character.abilities_score.lock().unwrap().strength.total(),
+        10
+    );
+}
+
+#[test]
+fn charges_recharge() {
+    let mut character = new_character("fighter");
+
+    character.insert_item(Item {
+        state: ItemState::Equipped,
+        ..magic_item(
+            "wand-of-magic-missiles",
+            vec![],
+            Some(Charges {
+                max: 7,
+                used: 0,
+                recharge: Some(Recharge::Dawn),
+            }),
+        )
+    });
+
+    character.use_charges("wand-of-magic-missiles", 5).unwrap();
+    assert!(character.use_charges("wand-of-magic-missiles", 3).is_err());
+
+    character.short_rest();
+    assert!(character.use_charges("wand-of-magic-missiles", 3).is_err());
+    character.use_charges("wand-of-magic-missiles", 2).unwrap();
+}
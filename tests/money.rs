@@ -0,0 +1,93 @@
+use common::new_character;
+use dnd_character::equipment::Item;
+use dnd_character::money::{Cost, Currency, Purse};
+
+mod common;
+
+#[test]
+fn parse_costs() {
+    assert_eq!(
+        "15 gp".parse::<Cost>().unwrap(),
+        Cost {
+            quantity: 15,
+            unit: Currency::Gp
+        }
+    );
+    assert_eq!("2sp".parse::<Cost>().unwrap().in_copper(), 20);
+    assert!("gp".parse::<Cost>().is_err());
+    assert!("3 zz".parse::<Cost>().is_err());
+}
+
+#[test]
+fn pay_with_change_and_exchange() {
+    let mut purse = Purse {
+        cp: 5,
+        pp: 1,
+        ..Purse::default()
+    };
+    assert_eq!(purse.total_in_copper(), 1005);
+
+    purse
+        .pay(Cost {
+            quantity: 2,
+            unit: Currency::Sp,
+        })
+        .unwrap();
+    assert_eq!(purse.total_in_copper(), 985);
+    assert_eq!(
+        purse,
+        Purse {
+            gp: 9,
+            sp: 8,
+            cp: 5,
+            ..Purse::default()
+        }
+    );
+
+    assert!(
+        purse
+            .pay(Cost {
+                quantity: 10,
+                unit: Currency::Gp,
+            })
+            .is_err()
+    );
+    assert_eq!(purse.total_in_copper(), 985);
+
+    purse.exchange(Currency::Gp, Currency::Ep, 3).unwrap();
+    assert_eq!((purse.gp, purse.ep), (6, 6));
+    assert!(purse.exchange(Currency::Sp, Currency::Gp, 8).is_err());
+    assert!(purse.exchange(Currency::Pp, Currency::Gp, 1).is_err());
+}
+
+#[test]
+fn buy_and_sell() {
+    let mut character = new_character("fighter");
+    character.money.gp = 10;
+
+    let longsword = Item {
+        cost: Some(Cost {
+            quantity: 15,
+            unit: Currency::Gp,
+        }),
+        ..Item::custom("longsword".to_string(), 1)
+    };
+    assert!(character.buy(longsword.clone()).is_err());
+    assert!(character.inventory.is_empty());
+
+    let dagger = Item {
+        cost: Some(Cost {
+            quantity: 2,
+            unit: Currency::Gp,
+        }),
+        ..Item::custom("dagger".to_string(), 3)
+    };
+    character.buy(dagger).unwrap();
+    assert_eq!(character.money.total_in_copper(), 400);
+    assert_eq!(character.inventory[0].quantity, 3);
+
+    assert!(character.sell("dagger", 4).is_err());
+    assert_eq!(character.sell("dagger", 2).unwrap(), 200);
+    assert_eq!(character.money.total_in_copper(), 600);
+    assert_eq!(character.inventory[0].quantity, 1);
+}
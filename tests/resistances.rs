@@ -0,0 +1,139 @@
+use dnd_character::Character;
+use dnd_character::classes::{ClassResource, ClassResourceType, RestType};
+use dnd_character::equipment::{Item, ItemState};
+use dnd_character::hit_points::HitPointEvent;
+use dnd_character::magic_items::{MagicItem, MagicItemEffect, Rarity};
+use dnd_character::resistances::{DamageResistances, dragon_ancestor_damage_type};
+
+mod common;
+
+fn new_character(class: &str, race: &str) -> Character {
+    let mut character = common::new_character_of_race(class, race, race);
+    character.hit_dice_result = 30;
+    character.hp = character.max_hp();
+    character
+}
+
+#[test]
+fn apply_resistances() {
+    let mut resistances = DamageResistances::default();
+    assert_eq!(resistances.apply(7, "fire"), 7);
+
+    resistances.resistances.insert("fire".to_string());
+    assert_eq!(resistances.apply(7, "fire"), 3);
+
+    resistances.vulnerabilities.insert("fire".to_string());
+    assert_eq!(resistances.apply(7, "fire"), 6);
+
+    resistances.immunities.insert("fire".to_string());
+    assert_eq!(resistances.apply(7, "fire"), 0);
+    assert_eq!(resistances.apply(7, "cold"), 7);
+
+    assert_eq!(
+        dragon_ancestor_damage_type("dragon-ancestor-red---fire-damage"),
+        Some("fire")
+    );
+}
+
+#[test]
+fn race_resistances() {
+    let mut character = new_character("fighter", "dwarf");
+    let hp = character.hp;
+
+    let events = character.take_damage(9, "poison");
+    assert_eq!(
+        events[0],
+        HitPointEvent::DamageTaken {
+            amount: 4,
+            damage_type: "poison".to_string()
+        }
+    );
+    assert_eq!(character.hp, hp - 4);
+
+    character
+        .damage_resistances
+        .immunities
+        .insert("poison".to_string());
+    assert!(character.take_damage(9, "poison").is_empty());
+}
+
+#[test]
+fn rage_resistances() {
+    let mut character = new_character("barbarian", "human");
+    let barbarian = &mut character.classes.0.get_mut("barbarian").unwrap().1;
+    barbarian.resources.insert(
+        ClassResourceType::Rage,
+        ClassResource::new(1, RestType::Long),
+    );
+
+    barbarian.start_rage().unwrap();
+    assert!(barbarian.start_rage().is_err());
+    assert!(
+        character
+            .effective_damage_resistances()
+            .resistances
+            .contains("slashing")
+    );
+
+    character.short_rest();
+    assert!(
+        character
+            .effective_damage_resistances()
+            .resistances
+            .is_empty()
+    );
+
+    // No rage left until the long rest
+    let barbarian = &mut character.classes.0.get_mut("barbarian").unwrap().1;
+    assert!(barbarian.start_rage().is_err());
+}
+
+#[test]
+fn class_and_item_resistances() {
+    let mut character = new_character("sorcerer", "human");
+    let sorcerer = &mut character.classes.0.get_mut("sorcerer").unwrap().1;
+    sorcerer.subclass = Some("draconic".to_string());
+    sorcerer.sorcerer_dragon_ancestor = Some("dragon-ancestor-white---cold-damage".to_string());
+    sorcerer.level = 17;
+    assert!(
+        character
+            .effective_damage_resistances()
+            .resistances
+            .is_empty()
+    );
+
+    character.classes.0.get_mut("sorcerer").unwrap().1.level = 18;
+    assert!(
+        character
+            .effective_damage_resistances()
+            .resistances
+            .contains("cold")
+    );
+
+    character.insert_item(Item {
+        magic: Some(MagicItem {
+            rarity: Rarity::Rare,
+            requires_attunement: false,
+            attuned: false,
+            effects: vec![MagicItemEffect::DamageImmunity("poison".to_string())],
+            charges: None,
+        }),
+        ..Item::custom("periapt-of-proof-against-poison".to_string(), 1)
+    });
+    assert!(
+        character
+            .effective_damage_resistances()
+            .immunities
+            .is_empty()
+    );
+
+    character
+        .set_item_state("periapt-of-proof-against-poison", ItemState::Equipped)
+        .unwrap();
+    assert!(
+        character
+            .effective_damage_resistances()
+            .immunities
+            .contains("poison")
+    );
+}
@@ -0,0 +1,241 @@
+use common::new_character;
+use dnd_character::classes::{
+    ClassResource, ClassResourceType, RestType, SpellSlotCreation, UsableSlots,
+};
+
+mod common;
+
+#[test]
+fn spend_and_restore_class_resources() {
+    let mut character = new_character("fighter");
+
+    let fighter = &mut character.classes.0.get_mut("fighter").unwrap().1;
+    fighter.resources.insert(
+        ClassResourceType::ActionSurge,
+        ClassResource::new(1, RestType::Short),
+    );
+    fighter.resources.insert(
+        ClassResourceType::Indomitable,
+        ClassResource::new(2, RestType::Long),
+    );
+
+    fighter
+        .spend_resource(ClassResourceType::ActionSurge, 1)
+        .unwrap();
+    fighter
+        .spend_resource(ClassResourceType::Indomitable, 2)
+        .unwrap();
+    assert!(
+        fighter
+            .spend_resource(ClassResourceType::ActionSurge, 1)
+            .is_err()
+    );
+    assert!(fighter.spend_resource(ClassResourceType::Ki, 1).is_err());
+
+    character.short_rest();
+
+    let fighter = &mut character.classes.0.get_mut("fighter").unwrap().1;
+    assert_eq!(
+        fighter.resources[&ClassResourceType::ActionSurge].remaining(),
+        1
+    );
+    assert_eq!(
+        fighter.resources[&ClassResourceType::Indomitable].remaining(),
+        0
+    );
+
+    fighter.restore_resource(ClassResourceType::Indomitable, 5);
+    assert_eq!(
+        fighter.resources[&ClassResourceType::Indomitable].remaining(),
+        2
+    );
+
+    fighter
+        .spend_resource(ClassResourceType::Indomitable, 1)
+        .unwrap();
+    character.classes.rest(RestType::Long);
+
+    let fighter = &character.classes.0["fighter"].1;
+    assert_eq!(
+        fighter.resources[&ClassResourceType::Indomitable].remaining(),
+        2
+    );
+}
+
+#[test]
+fn flexible_casting_and_metamagic() {
+    let mut character = new_character("sorcerer");
+
+    let sorcerer = &mut character.classes.0.get_mut("sorcerer").unwrap().1;
+    sorcerer.resources.insert(
+        ClassResourceType::SorceryPoints,
+        ClassResource::new(3, RestType::Long),
+    );
+    sorcerer.sorcerer_slot_creation = Some(vec![
+        SpellSlotCreation {
+            spell_slot_level: 1,
+            sorcery_point_cost: 2,
+        },
+        SpellSlotCreation {
+            spell_slot_level: 2,
+            sorcery_point_cost: 3,
+        },
+    ]);
+    sorcerer.sorcerer_metamagic = Some(vec!["metamagic-quickened-spell".to_string()]);
+    *sorcerer.usable_slots_mut().unwrap() = UsableSlots {
+        level_1: 1,
+        level_2: 1,
+        ..UsableSlots::default()
+    };
+
+    sorcerer.create_spell_slot(1).unwrap();
+    assert_eq!(sorcerer.usable_slots().unwrap().level_1, 2);
+    assert!(sorcerer.create_spell_slot(2).is_err());
+    assert!(sorcerer.create_spell_slot(6).is_err());
+
+    sorcerer.convert_spell_slot(2).unwrap();
+    assert_eq!(sorcerer.usable_slots().unwrap().level_2, 0);
+    assert_eq!(
+        sorcerer.resources[&ClassResourceType::SorceryPoints].remaining(),
+        3
+    );
+
+    // The sorcery points are full, the slot is kept
+    assert!(sorcerer.convert_spell_slot(1).is_err());
+    assert_eq!(sorcerer.usable_slots().unwrap().level_1, 2);
+
+    let unknown = sorcerer
+        .cast_spell(1, &["metamagic-twinned-spell"])
+        .unwrap_err();
+    assert_eq!(
+        unknown.to_string(),
+        "Metamagic metamagic-twinned-spell is not known"
+    );
+
+    // Known, but without a sorcery point cost
+    sorcerer
+        .sorcerer_metamagic
+        .as_mut()
+        .unwrap()
+        .push("metamagic-transmuted-spell".to_string());
+    let unsupported = sorcerer
+        .cast_spell(1, &["metamagic-transmuted-spell"])
+        .unwrap_err();
+    assert_ne!(unsupported.to_string(), unknown.to_string());
+    assert_eq!(sorcerer.usable_slots().unwrap().level_1, 2);
+    sorcerer
+        .cast_spell(1, &["metamagic-quickened-spell"])
+        .unwrap();
+    assert_eq!(sorcerer.usable_slots().unwrap().level_1, 1);
+    assert_eq!(
+        sorcerer.resources[&ClassResourceType::SorceryPoints].remaining(),
+        1
+    );
+
+    assert!(
+        sorcerer
+            .cast_spell(1, &["metamagic-quickened-spell"])
+            .is_err()
+    );
+    assert_eq!(sorcerer.usable_slots().unwrap().level_1, 1);
+}
+
+#[test]
+fn arcane_recovery() {
+    let mut character = new_character("wizard");
+
+    let max_slots = UsableSlots {
+        level_1: 4,
+        level_2: 3,
+        level_3: 2,
+        ..UsableSlots::default()
+    };
+
+    let wizard = &mut character.classes.0.get_mut("wizard").unwrap().1;
+    wizard.wizard_arcane_recovery_levels = Some(3);
+    wizard.resources.insert(
+        ClassResourceType::ArcaneRecovery,
+        ClassResource::new(1, RestType::Long),
+    );
+    *wizard.usable_slots_mut().unwrap() = UsableSlots {
+        level_1: 3,
+        level_2: 1,
+        level_3: 2,
+        ..UsableSlots::default()
+    };
+
+    // A short rest resource shows whether the failed recoveries rested
+    wizard.resources.insert(
+        ClassResourceType::ChannelDivinity,
+        ClassResource::new(1, RestType::Short),
+    );
+    wizard
+        .spend_resource(ClassResourceType::ChannelDivinity, 1)
+        .unwrap();
+
+    assert!(
+        character
+            .short_rest_recovering_slots(&[2, 2], &max_slots)
+            .is_err()
+    );
+    assert!(
+        character
+            .short_rest_recovering_slots(&[3], &max_slots)
+            .is_err()
+    );
+    assert!(
+        character
+            .short_rest_recovering_slots(&[1, 1], &max_slots)
+            .is_err()
+    );
+    let wizard = &character.classes.0["wizard"].1;
+    assert_eq!(
+        wizard.resources[&ClassResourceType::ChannelDivinity].used,
+        1
+    );
+
+    character
+        .short_rest_recovering_slots(&[2, 1], &max_slots)
+        .unwrap();
+    let wizard = &mut character.classes.0.get_mut("wizard").unwrap().1;
+    assert_eq!(
+        wizard.resources[&ClassResourceType::ChannelDivinity].used,
+        0
+    );
+    assert_eq!(wizard.usable_slots().unwrap().level_1, 4);
+    assert_eq!(wizard.usable_slots().unwrap().level_2, 2);
+
+    // Arcane Recovery was already used today
+    wizard.usable_slots_mut().unwrap().level_1 = 0;
+    wizard
+        .spend_resource(ClassResourceType::ChannelDivinity, 1)
+        .unwrap();
+    assert!(
+        character
+            .short_rest_recovering_slots(&[1], &max_slots)
+            .is_err()
+    );
+    let wizard = &character.classes.0["wizard"].1;
+    assert_eq!(
+        wizard.resources[&ClassResourceType::ChannelDivinity].used,
+        1
+    );
+
+    character.classes.rest(RestType::Long);
+    character
+        .short_rest_recovering_slots(&[1], &max_slots)
+        .unwrap();
+    assert_eq!(
+        character.classes.0["wizard"]
+            .1
+            .usable_slots()
+            .unwrap()
+            .level_1,
+        1
+    );
+    assert!(
+        character
+            .short_rest_recovering_slots(&[1], &max_slots)
+            .is_err()
+    );
+}
@@ -0,0 +1,155 @@
+use common::new_character;
+use dnd_character::classes::ClassSpellCasting;
+use dnd_character::spells::{CastingMode, SpellDetails};
+
+mod common;
+
+#[test]
+fn spell_save_dc_and_attack_bonus() {
+    let mut character = new_character("wizard");
+    character.abilities_score.lock().unwrap().intelligence.score = 16;
+    // The experience is limited to one level at a time
+    for _ in 0..4 {
+        character.add_experience(6_500);
+    }
+    assert_eq!(character.proficiency_bonus(), 3);
+
+    let proficiency_bonus = character.proficiency_bonus();
+    let wizard = &character.classes.0["wizard"];
+    assert_eq!(wizard.spellcasting_ability(), Some("int"));
+    assert_eq!(wizard.spell_save_dc(proficiency_bonus), Some(14));
+    assert_eq!(wizard.spell_attack_bonus(proficiency_bonus), Some(6));
+
+    let character = new_character("fighter");
+    let fighter = &character.classes.0["fighter"];
+    assert_eq!(fighter.spellcasting_ability(), None);
+    assert_eq!(fighter.spell_save_dc(2), None);
+}
+
+fn ritual(index: &str, level: u8) -> SpellDetails {
+    SpellDetails {
+        school: "divination".to_string(),
+        duration: "Up to 10 minutes".to_string(),
+        concentration: true,
+        ritual: true,
+        ..common::spell(index, level)
+    }
+}
+
+#[test]
+fn ritual_casting() {
+    let detect_magic = ritual("detect-magic", 1);
+    let mut not_ritual = ritual("bless", 1);
+    not_ritual.ritual = false;
+
+    let mut character = new_character("cleric");
+    assert!(!character.classes.0["cleric"].can_cast_as_ritual(&detect_magic));
+
+    {
+        let cleric = &mut character.classes.0.get_mut("cleric").unwrap().1;
+        if let Some(ClassSpellCasting::AlreadyKnowPrepared {
+            spells_prepared_index,
+            usable_slots,
+            ..
+        }) = &mut cleric.spell_casting
+        {
+            *spells_prepared_index = vec![
+                vec![],
+                vec!["detect-magic".to_string(), "bless".to_string()],
+            ];
+            usable_slots.level_1 = 1;
+        }
+    }
+    assert!(character.classes.0["cleric"].can_cast_as_ritual(&detect_magic));
+    assert!(!character.classes.0["cleric"].can_cast_as_ritual(&not_ritual));
+
+    // The ritual doesn't use the spell slot
+    character
+        .cast_spell("cleric", &detect_magic, &CastingMode::Ritual)
+        .unwrap();
+    assert_eq!(character.concentration().unwrap().spell, "detect-magic");
+    assert_eq!(
+        character.classes.0["cleric"]
+            .1
+            .usable_slots()
+            .unwrap()
+            .level_1,
+        1
+    );
+
+    assert!(
+        character
+            .cast_spell("cleric", &not_ritual, &CastingMode::Ritual)
+            .is_err()
+    );
+    let dropped = character
+        .cast_spell(
+            "cleric",
+            &not_ritual,
+            &CastingMode::Slot {
+                level: 1,
+                metamagic: vec![],
+            },
+        )
+        .unwrap();
+    assert_eq!(dropped.unwrap().spell, "detect-magic");
+    assert_eq!(
+        character.classes.0["cleric"]
+            .1
+            .usable_slots()
+            .unwrap()
+            .level_1,
+        0
+    );
+}
+
+#[test]
+fn book_of_ancient_secrets() {
+    let detect_magic = ritual("detect-magic", 1);
+    let mut character = new_character("warlock");
+    let warlock = character.classes.0.get_mut("warlock").unwrap();
+    warlock.1.level = 3;
+
+    assert!(warlock.add_ritual_to_book(&detect_magic).is_err());
+
+    warlock.1.warlock_pact = Some("pact-of-the-tome".to_string());
+    warlock.1.warlock_eldritch_invocation = Some(vec![
+        "eldritch-invocation-book-of-ancient-secrets".to_string(),
+    ]);
+    assert!(!warlock.can_cast_as_ritual(&detect_magic));
+
+    warlock.add_ritual_to_book(&detect_magic).unwrap();
+    assert!(
+        warlock
+            .add_ritual_to_book(&ritual("phantom-steed", 3))
+            .is_err()
+    );
+    assert!(warlock.can_cast_as_ritual(&detect_magic));
+}
+
+#[test]
+fn wizard_spellbook_rituals() {
+    let detect_magic = ritual("detect-magic", 1);
+    let mut character = new_character("wizard");
+    assert!(!character.classes.0["wizard"].can_cast_as_ritual(&detect_magic));
+
+    let wizard = character.classes.0.get_mut("wizard").unwrap();
+    wizard.1.level = 1;
+    assert!(
+        wizard
+            .add_spell_to_spellbook(&ritual("phantom-steed", 3))
+            .is_err()
+    );
+    wizard.add_spell_to_spellbook(&detect_magic).unwrap();
+
+    // The ritual is in the spellbook without being prepared
+    assert!(wizard.can_cast_as_ritual(&detect_magic));
+    character
+        .cast_spell("wizard", &detect_magic, &CastingMode::Ritual)
+        .unwrap();
+    assert_eq!(character.concentration().unwrap().spell, "detect-magic");
+
+    let mut character = new_character("cleric");
+    let cleric = character.classes.0.get_mut("cleric").unwrap();
+    assert!(cleric.add_spell_to_spellbook(&detect_magic).is_err());
+}
@@ -0,0 +1,118 @@
+use common::new_character;
+use dnd_character::spells::{
+    AreaOfEffect, AreaOfEffectShape, DcSuccess, SpellComponent, SpellDc, SpellDetails, SpellFilter,
+};
+use std::collections::BTreeMap;
+
+mod common;
+
+fn fireball() -> SpellDetails {
+    SpellDetails {
+        name: "Fireball".to_string(),
+        school: "evocation".to_string(),
+        range: "150 feet".to_string(),
+        components: vec![
+            SpellComponent::Verbal,
+            SpellComponent::Somatic,
+            SpellComponent::Material,
+        ],
+        material: Some("A tiny ball of bat guano and sulfur.".to_string()),
+        damage_type: Some("fire".to_string()),
+        damage_at_slot_level: BTreeMap::from([
+            (3, "8d6".to_string()),
+            (4, "9d6".to_string()),
+            (5, "10d6".to_string()),
+        ]),
+        dc: Some(SpellDc {
+            ability: "dex".to_string(),
+            success: DcSuccess::Half,
+            desc: None,
+        }),
+        area_of_effect: Some(AreaOfEffect {
+            shape: AreaOfEffectShape::Sphere,
+            size: 20,
+        }),
+        classes: vec!["sorcerer".to_string(), "wizard".to_string()],
+        subclasses: vec!["lore".to_string()],
+        ..common::spell("fireball", 3)
+    }
+}
+
+#[test]
+fn damage_by_slot_level() {
+    let spell = fireball();
+
+    assert_eq!(spell.damage_at_slot(2), None);
+    assert_eq!(spell.damage_at_slot(3), Some("8d6"));
+    assert_eq!(spell.damage_at_slot(9), Some("10d6"));
+    assert_eq!(spell.heal_at_slot(3), None);
+}
+
+#[test]
+fn filter_spells() {
+    let spell = fireball();
+
+    assert!(SpellFilter::default().matches(&spell));
+    assert!(
+        SpellFilter {
+            class: Some("wizard".to_string()),
+            levels: vec![1, 3],
+            dc_type: Some("dex".to_string()),
+            area_of_effect: Some(AreaOfEffectShape::Sphere),
+            name: Some("fire".to_string()),
+            ..Default::default()
+        }
+        .matches(&spell)
+    );
+
+    assert!(
+        !SpellFilter {
+            class: Some("cleric".to_string()),
+            ..Default::default()
+        }
+        .matches(&spell)
+    );
+    assert!(
+        !SpellFilter {
+            concentration: Some(true),
+            ..Default::default()
+        }
+        .matches(&spell)
+    );
+    assert!(
+        !SpellFilter {
+            levels: vec![0],
+            ..Default::default()
+        }
+        .matches(&spell)
+    );
+}
+
+#[test]
+fn cantrip_damage_by_character_level() {
+    let mut character = new_character("wizard");
+
+    let fire_bolt = SpellDetails {
+        index: "fire-bolt".to_string(),
+        name: "Fire Bolt".to_string(),
+        level: 0,
+        damage_at_slot_level: BTreeMap::new(),
+        damage_at_character_level: BTreeMap::from([
+            (1, "1d10".to_string()),
+            (5, "2d10".to_string()),
+            (11, "3d10".to_string()),
+            (17, "4d10".to_string()),
+        ]),
+        ..fireball()
+    };
+
+    assert_eq!(character.cantrip_damage(&fire_bolt), Some("1d10"));
+    assert_eq!(character.cantrip_damage(&fireball()), None);
+
+    // The experience is limited to one level at a time
+    for _ in 0..4 {
+        character.add_experience(6_500);
+    }
+    assert_eq!(character.level(), 5);
+    assert_eq!(character.cantrip_damage(&fire_bolt), Some("2d10"));
+}
@@ -0,0 +1,153 @@
+use common::new_character;
+use dnd_character::starting_equipment::{
+    ItemQuantity, StartingEquipment, StartingEquipmentChoice, StartingEquipmentOption,
+    StartingEquipmentSelection,
+};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+mod common;
+
+fn item(index: &str, quantity: u16) -> ItemQuantity {
+    ItemQuantity {
+        index: index.to_string(),
+        quantity,
+    }
+}
+
+fn martial_weapons(choose: u8) -> StartingEquipmentOption {
+    StartingEquipmentOption::Category {
+        choose,
+        category: "martial-weapons".to_string(),
+        items: vec!["longsword".to_string(), "warhammer".to_string()],
+    }
+}
+
+fn fighter_equipment() -> StartingEquipment {
+    StartingEquipment {
+        items: vec![],
+        choices: vec![
+            StartingEquipmentChoice {
+                desc: "(a) chain mail or (b) leather armor, longbow, and 20 arrows".to_string(),
+                choose: 1,
+                options: vec![
+                    StartingEquipmentOption::Item(item("chain-mail", 1)),
+                    StartingEquipmentOption::Multiple(vec![
+                        StartingEquipmentOption::Item(item("leather-armor", 1)),
+                        StartingEquipmentOption::Item(item("longbow", 1)),
+                        StartingEquipmentOption::Item(item("arrow", 20)),
+                    ]),
+                ],
+            },
+            StartingEquipmentChoice {
+                desc: "(a) a martial weapon and a shield or (b) two martial weapons".to_string(),
+                choose: 1,
+                options: vec![
+                    StartingEquipmentOption::Multiple(vec![
+                        martial_weapons(1),
+                        StartingEquipmentOption::Item(item("shield", 1)),
+                    ]),
+                    martial_weapons(2),
+                ],
+            },
+        ],
+    }
+}
+
+fn select(option: usize, picks: &[&str]) -> Vec<StartingEquipmentSelection> {
+    vec![StartingEquipmentSelection {
+        option,
+        picks: picks.iter().map(|pick| pick.to_string()).collect(),
+    }]
+}
+
+#[test]
+fn resolve_starting_equipment() {
+    let equipment = fighter_equipment();
+
+    let items = equipment
+        .resolve(&[select(1, &[]), select(0, &["longsword"])])
+        .unwrap();
+    assert_eq!(
+        items,
+        vec![
+            item("leather-armor", 1),
+            item("longbow", 1),
+            item("arrow", 20),
+            item("longsword", 1),
+            item("shield", 1),
+        ]
+    );
+
+    let items = equipment
+        .resolve(&[select(0, &[]), select(1, &["warhammer", "longsword"])])
+        .unwrap();
+    assert_eq!(
+        items,
+        vec![
+            item("chain-mail", 1),
+            item("warhammer", 1),
+            item("longsword", 1)
+        ]
+    );
+}
+
+#[test]
+fn invalid_starting_equipment_selection() {
+    let equipment = fighter_equipment();
+
+    // A choice without selection
+    assert!(equipment.resolve(&[select(0, &[])]).is_err());
+    // Unknown option
+    assert!(
+        equipment
+            .resolve(&[select(2, &[]), select(1, &["longsword", "warhammer"])])
+            .is_err()
+    );
+    // Missing pick
+    assert!(
+        equipment
+            .resolve(&[select(0, &[]), select(1, &["longsword"])])
+            .is_err()
+    );
+    // Pick outside the category
+    assert!(
+        equipment
+            .resolve(&[select(0, &[]), select(0, &["dagger"])])
+            .is_err()
+    );
+    // Picks for an option without categories
+    assert!(
+        equipment
+            .resolve(&[select(0, &["longsword"]), select(0, &["longsword"])])
+            .is_err()
+    );
+    // Two options where only one is allowed
+    let mut two_options = select(0, &[]);
+    two_options.extend(select(1, &[]));
+    assert!(
+        equipment
+            .resolve(&[two_options, select(0, &["longsword"])])
+            .is_err()
+    );
+}
+
+#[test]
+fn take_starting_gold() {
+    let mut character = new_character("fighter");
+    let gold_before = character.money.gp;
+
+    let gold = character
+        .take_starting_gold("fighter", &mut StdRng::seed_from_u64(42))
+        .unwrap();
+
+    assert!((50..=200).contains(&gold));
+    assert!(gold.is_multiple_of(10));
+    assert_eq!(character.money.gp, gold_before + gold);
+
+    assert!(
+        character
+            .take_starting_gold("commoner", &mut StdRng::seed_from_u64(42))
+            .is_err()
+    );
+}